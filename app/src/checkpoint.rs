@@ -0,0 +1,143 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use engine::id::{CompetitorId, SeasonId};
+use engine::repo::model::Competition;
+use serde_derive::{Deserialize, Serialize};
+use sport_radar::model::{Competitor, PlayerStatisticsResponse};
+use tracing::warn;
+
+use crate::scrapper::Query;
+
+/// The season resolved by the first steps of a scrape, so resuming skips them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeasonCheckpoint {
+    pub competition: Competition,
+    pub season_id: SeasonId,
+    pub competitors: Vec<Competitor>,
+}
+
+/// The progress of a scrape, saved after every step so that rerunning the query resumes it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub query: Query,
+    pub created_at: DateTime<Utc>,
+    pub season: Option<SeasonCheckpoint>,
+    /// The statistics of the competitors done so far.
+    pub done: BTreeMap<CompetitorId, PlayerStatisticsResponse>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl Checkpoint {
+    /// The checkpoint of the query in the given directory, or a new one if there is none.
+    pub fn load(dir: &Path, query: &Query) -> Result<Self> {
+        let path = Self::path_for(dir, query);
+        if !path.exists() {
+            return Ok(Checkpoint {
+                query: query.clone(),
+                created_at: Utc::now(),
+                season: None,
+                done: BTreeMap::new(),
+                path,
+            });
+        }
+
+        let content = fs::read_to_string(&path)
+            .map_err(|e| anyhow!("Failed to read checkpoint {}: {}", path.display(), e))?;
+        let checkpoint: Checkpoint = serde_json::from_str(&content)
+            .map_err(|e| anyhow!("Invalid checkpoint {}: {}", path.display(), e))?;
+        if checkpoint.query != *query {
+            return Err(anyhow!(
+                "Checkpoint {} belongs to another query, remove it or pass --restart",
+                path.display()
+            ));
+        }
+        Ok(Checkpoint { path, ..checkpoint })
+    }
+
+    /// Every checkpoint in the directory, skipping the unreadable ones.
+    pub fn list(dir: &Path) -> Result<Vec<Self>> {
+        if !dir.exists() {
+            return Ok(vec![]);
+        }
+        let mut checkpoints = vec![];
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let checkpoint = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| {
+                    serde_json::from_str::<Checkpoint>(&content).map_err(|e| e.to_string())
+                });
+            match checkpoint {
+                Ok(checkpoint) => checkpoints.push(Checkpoint { path, ..checkpoint }),
+                Err(e) => warn!(checkpoint = %path.display(), error = %e, "Skipping checkpoint"),
+            }
+        }
+        Ok(checkpoints)
+    }
+
+    /// Removes the checkpoint of the query, whatever its state.
+    pub fn discard(dir: &Path, query: &Query) -> Result<()> {
+        let path = Self::path_for(dir, query);
+        if path.exists() {
+            fs::remove_file(&path)
+                .map_err(|e| anyhow!("Failed to remove checkpoint {}: {}", path.display(), e))?;
+        }
+        Ok(())
+    }
+
+    fn path_for(dir: &Path, query: &Query) -> PathBuf {
+        dir.join(format!("{}.json", file_stem(query)))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Writes the checkpoint, through a temporary file so a crash never leaves it half written.
+    pub fn save(&self) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec(self)?)
+            .and_then(|_| fs::rename(&tmp, &self.path))
+            .map_err(|e| anyhow!("Failed to write checkpoint {}: {}", self.path.display(), e))
+    }
+
+    /// Removes the checkpoint once the scrape is complete.
+    pub fn remove(&self) -> Result<()> {
+        if self.path.exists() {
+            fs::remove_file(&self.path).map_err(|e| {
+                anyhow!("Failed to remove checkpoint {}: {}", self.path.display(), e)
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// A readable file name for the query, such as `premier-league_england_male_2023-08-11_2024-05-19`.
+fn file_stem(query: &Query) -> String {
+    let slug = |text: &str| {
+        text.to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("-")
+    };
+    format!(
+        "{}_{}_{}_{}_{}",
+        slug(&query.event),
+        slug(&query.location),
+        slug(&format!("{:?}", query.gender)),
+        query.season_start,
+        query.season_end
+    )
+}
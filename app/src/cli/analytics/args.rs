@@ -1,41 +1,129 @@
-use crate::cli::utils::{DimensionArg, GenderArg, MetricArg, SortArg, SportArg};
+use crate::cli::args::QueryArgs;
+use crate::cli::utils::{DimensionArg, MetricArg, SortArg};
 
-use chrono::NaiveDate;
 use clap::Args;
+use engine::{
+    aggregate::{Aggregate, Aggregation, GroupBy},
+    engine::{Query, Ranking, Sort, SortKey, Tiebreaker},
+    filter::Predicate,
+};
 
 #[derive(Debug, Args)]
 pub struct AnalyticsArgs {
-    #[arg(long, value_enum, short = 'S')]
-    /// Sport to analyze (football)
-    pub sport: SportArg,
-    #[arg(long, short = 'E')]
-    /// The event name, such as "Premier's League"
-    pub event: String,
-    #[arg(long, short = 'L')]
-    /// The location of the event, such as England, International, etc...
-    pub location: String,
-    #[arg(long, value_enum, short = 'G')]
-    /// Analyze data by gender (male, female)
-    pub gender: GenderArg,
-    #[arg(long)]
-    /// The start date of the season in YYYY-MM-DD
-    pub season_start: NaiveDate,
-    #[arg(long)]
-    /// The end date of the season in YYYY-MM-DD
-    pub season_end: NaiveDate,
+    #[command(flatten)]
+    pub query: QueryArgs,
     #[arg(long, value_enum, short = 'd')]
     /// Entity to analyze (player, team)
     pub dimension: DimensionArg,
-    #[arg(long, value_enum, short = 'm')]
-    /// Metric to analyze (score, assist)
-    pub metric: MetricArg,
-    #[arg(long, value_enum, short = 's')]
-    /// Analyze data in order (asc, desc)
-    pub sort: SortArg,
+    #[arg(
+        long,
+        value_enum,
+        short = 'm',
+        value_delimiter = ',',
+        required_unless_present = "group_by"
+    )]
+    /// Metrics to analyze, comma separated (score, assist, or metric names such as corner_kicks)
+    pub metric: Vec<MetricArg>,
+    #[arg(
+        long,
+        value_enum,
+        short = 's',
+        value_delimiter = ',',
+        required_unless_present = "group_by"
+    )]
+    /// Sort keys, comma separated, such as goals_scored:desc,matches_played:asc
+    /// (asc or desc alone sorts by the first metric, or the groups by their aggregates)
+    pub sort: Vec<SortArg>,
+    #[arg(long, value_delimiter = ',')]
+    /// (optional) Order the rows tied on every sort key, comma separated,
+    /// such as matches_played:asc,name
+    pub tiebreak: Vec<Tiebreaker>,
+    #[arg(long, default_value = "standard")]
+    /// How tied rows are ranked (standard 1224, dense 1223, ordinal 1234)
+    pub ranking: Ranking,
+    #[arg(long)]
+    /// (optional) The number of ranked results to skip, for the next pages
+    pub offset: Option<u32>,
     #[arg(long, value_enum, short = 'l')]
     /// The maximum number of results to return
     pub limit: u32,
+    #[arg(long = "where", value_name = "PREDICATE")]
+    /// (optional) Keep the rows matching, such as "goals_scored >= 10 and team = LIV"
+    pub filter: Option<Predicate>,
+    #[arg(long, requires = "aggregate")]
+    /// (optional) Group the players by team or competition, instead of ranking them
+    pub group_by: Option<GroupBy>,
+    #[arg(long, value_delimiter = ',', requires = "group_by")]
+    /// Aggregates of the grouped players, comma separated, such as avg(goals_scored),count(assists)
+    /// (sum, avg, min, max, count), count also takes a condition such as count(goals_scored > 0)
+    pub aggregate: Vec<Aggregate>,
+    #[arg(long)]
+    /// (optional) Leave out the players (or teams) that played fewer matches
+    pub min_matches: Option<u32>,
     #[arg(long, value_enum, short = 't')]
     /// (optional) Timeout for the request in millis
     pub timeout: Option<u32>,
+    #[arg(long)]
+    /// (optional) Print the provider calls the query would make, without making them
+    pub dry_run: bool,
+    #[arg(long)]
+    /// (optional) Fail instead of answering when some competitors could not be scraped
+    pub strict: bool,
+    #[arg(long)]
+    /// (optional) Fetch the competitors that failed once more before answering
+    pub retry_failed: bool,
+    #[arg(long)]
+    /// (optional) Discard the progress saved by a previous run of the query and start over
+    pub restart: bool,
+    #[arg(long)]
+    /// (optional) Answer from the data synced by `sport-cli sync`, without calling the provider
+    pub offline: bool,
+}
+
+impl From<&AnalyticsArgs> for Query {
+    fn from(args: &AnalyticsArgs) -> Self {
+        Query {
+            event: args.query.event.to_string(),
+            location: args.query.location.to_string(),
+            gender: args.query.gender.value.clone(),
+            season_start: args.query.season_start,
+            season_end: args.query.season_end,
+            dimension: args.dimension.value.clone(),
+            metrics: args.metric.iter().map(|arg| arg.value.clone()).collect(),
+            sort: args
+                .sort
+                .iter()
+                .map(|arg| SortKey {
+                    metric: arg
+                        .metric
+                        .clone()
+                        .or_else(|| args.metric.first().map(|arg| arg.value.clone()))
+                        .unwrap_or_default(),
+                    order: arg.value.clone(),
+                })
+                .collect(),
+            tiebreakers: args.tiebreak.clone(),
+            ranking: args.ranking.clone(),
+            offset: args.offset.unwrap_or_default(),
+            limit: args.limit,
+            min_matches: args.min_matches.unwrap_or_default(),
+            filter: args.filter.clone(),
+        }
+    }
+}
+
+impl AnalyticsArgs {
+    /// The aggregation of the grouped players, if grouped, ranked in the order of the
+    /// first sort key (descending by default).
+    pub fn aggregation(&self) -> Option<Aggregation> {
+        Some(Aggregation {
+            group_by: self.group_by.clone()?,
+            aggregates: self.aggregate.clone(),
+            order: self
+                .sort
+                .first()
+                .map(|arg| arg.value.clone())
+                .unwrap_or(Sort::Desc),
+        })
+    }
 }
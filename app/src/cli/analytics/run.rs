@@ -1,55 +1,194 @@
 use crate::{
     db::Db,
     scrapper::{Query, Scrapper},
+    store::Store,
 };
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use engine::{
-    engine::{Dimension, Engine, MetricKind, Query as EngineQuery, QueryResponse, Sort},
-    repo::{in_memo::InMemoRepository, model::PlayerDetails},
+    aggregate::Aggregation,
+    engine::{Dimension, Engine, Query as EngineQuery},
+    repo::in_memo::InMemoRepository,
 };
 use sport_radar::client::SportRadarClient;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
+use tracing::{debug, info};
 
-pub async fn run(query: Query) -> Result<()> {
+/// How to run an analytics query, from the cli flags.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunOptions {
+    pub dry_run: bool,
+    pub strict: bool,
+    pub retry_failed: bool,
+    pub restart: bool,
+}
+
+pub async fn run(
+    sport_data_source: SportRadarClient,
+    checkpoints: PathBuf,
+    store: &Store,
+    query: EngineQuery,
+    aggregation: Option<Aggregation>,
+    options: RunOptions,
+) -> Result<()> {
+    check(&query, aggregation.as_ref())?;
+    let scope: Query = (&query).into();
     let db = Arc::new(Mutex::new(Db::new()));
-    let sport_data_source = Arc::new(SportRadarClient::from_env());
+    let sport_data_source = Arc::new(sport_data_source);
+    let scrapper = Scrapper::default().with_checkpoints(checkpoints);
+
+    if options.dry_run {
+        // Keep the checkpoint a restart would discard, planning a fresh scrape instead
+        let planner = if options.restart {
+            Scrapper::default()
+        } else {
+            scrapper
+        };
+        let planner = planner.with_store(store.clone());
+        println!("{}", planner.plan(&sport_data_source, &scope)?);
+        return Ok(());
+    }
+    if options.restart {
+        scrapper.restart(&scope)?;
+    }
 
-    Scrapper
-        .execute(sport_data_source, &query, Arc::clone(&db))
+    let report = scrapper
+        .execute(
+            sport_data_source,
+            &scope,
+            Arc::clone(&db),
+            options.retry_failed,
+        )
         .await?;
+    if report.is_complete() {
+        info!("{}", report);
+    } else {
+        eprintln!("WARNING: {}", report);
+        if options.strict {
+            return Err(anyhow!(
+                "{} competitor(s) could not be scraped (--strict)",
+                report.failed.len()
+            ));
+        }
+    }
 
     let db = db.lock().unwrap();
-    dbg!(db.competitions.all().len());
-    dbg!(db.teams.all().len());
-    dbg!(db.players.all().len());
-    dbg!(db.players_stats.all().len());
-
-    let query = EngineQuery {
-        event: query.event,
-        location: query.location,
-        gender: query.gender,
-        season_start: query.season_start,
-        season_end: query.season_end,
-        dimension: Dimension::Player,
-        metric: MetricKind::GoalsScored,
-        sort: Sort::Desc,
-        limit: 2,
-    };
-
-    let results: Vec<QueryResponse<PlayerDetails>> = Engine.execute(
-        &db.players,
-        &db.teams,
-        &db.players_stats,
-        &db.competitions,
-        &query,
+    debug!(
+        competitions = db.competitions.all().len(),
+        teams = db.teams.all().len(),
+        players = db.players.all().len(),
+        players_stats = db.players_stats.all().len(),
+        "Scrapped"
     );
+    answer(&db, &query, aggregation.as_ref());
 
-    for result in results {
-        dbg!(result);
-    }
+    Ok(())
+}
 
-    drop(db);
+/// Answers the query from the data synced into the store, without calling the provider.
+pub fn run_offline(
+    store: &Store,
+    query: EngineQuery,
+    aggregation: Option<Aggregation>,
+) -> Result<()> {
+    check(&query, aggregation.as_ref())?;
+    let Some((db, _)) = store.load(&(&query).into())? else {
+        return Err(anyhow!(
+            "No synced data for {} ({}) {} to {} in {}, run `sport-cli sync` first",
+            query.event,
+            query.location,
+            query.season_start,
+            query.season_end,
+            store.path().display()
+        ));
+    };
+    answer(&db, &query, aggregation.as_ref());
 
     Ok(())
 }
+
+/// Rejects the queries the engine cannot answer, before scraping for them.
+fn check(query: &EngineQuery, aggregation: Option<&Aggregation>) -> Result<()> {
+    if let Some(aggregation) = aggregation {
+        if query.dimension == Dimension::Team {
+            return Err(anyhow!(
+                "--group-by groups player rows, use --dimension player"
+            ));
+        }
+        if let Some(aggregate) = aggregation.aggregates.iter().find(|aggregate| {
+            aggregate
+                .metrics()
+                .iter()
+                .any(|metric| !metric.is_player_metric())
+        }) {
+            return Err(anyhow!(
+                "{} aggregates a team metric, players do not have it",
+                aggregate
+            ));
+        }
+    }
+    let filter = query.filter.as_ref();
+    if query.dimension == Dimension::Team && filter.is_some_and(|f| f.is_about_players()) {
+        return Err(anyhow!(
+            "Team rows have no player name to filter on, use --dimension player"
+        ));
+    }
+    let metrics = query
+        .metrics
+        .iter()
+        .chain(query.sort.iter().map(|key| &key.metric))
+        .chain(filter.map(|filter| filter.metrics()).unwrap_or_default());
+    for metric in metrics {
+        if query.dimension == Dimension::Player && !metric.is_player_metric() {
+            return Err(anyhow!(
+                "{} is a team metric, use --dimension team",
+                metric.name()
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn answer(db: &Db, query: &EngineQuery, aggregation: Option<&Aggregation>) {
+    if let Some(aggregation) = aggregation {
+        let results = Engine.aggregate(
+            &db.players,
+            &db.teams,
+            &db.players_stats,
+            &db.competitions,
+            query,
+            aggregation,
+        );
+        for result in results {
+            println!("{:?}", result);
+        }
+        return;
+    }
+    match query.dimension {
+        Dimension::Player => {
+            let results = Engine.execute(
+                &db.players,
+                &db.teams,
+                &db.players_stats,
+                &db.competitions,
+                query,
+            );
+            for result in results {
+                println!("{:?}", result);
+            }
+        }
+        Dimension::Team => {
+            let results = Engine.execute_teams(
+                &db.teams,
+                &db.teams_stats,
+                &db.players_stats,
+                &db.competitions,
+                query,
+            );
+            for result in results {
+                println!("{:?}", result);
+            }
+        }
+    }
+}
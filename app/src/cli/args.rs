@@ -1,8 +1,23 @@
-use clap::{Parser, Subcommand};
+use chrono::NaiveDate;
+use clap::{ArgAction, Args, Parser, Subcommand};
+use sport_radar::config::{ConfigLayer, ConfigOptions, Format};
+use std::path::PathBuf;
+
+use crate::logging::LogFormat;
+use crate::scrapper::Query;
 
 use super::{
-    analytics::args::AnalyticsArgs, competitions::args::CompetitionsArgs,
-    players::args::PlayersArgs, seasons::args::SeasonsArgs, teams::args::TeamsArgs,
+    analytics::args::AnalyticsArgs,
+    competitions::args::CompetitionsArgs,
+    doctor::args::DoctorArgs,
+    players::args::PlayersArgs,
+    query::args::TextQueryArgs,
+    quota::args::QuotaArgs,
+    seasons::args::SeasonsArgs,
+    snapshot::args::SnapshotArgs,
+    sync::args::SyncArgs,
+    teams::args::TeamsArgs,
+    utils::{GenderArg, SportArg},
 };
 
 #[derive(Debug, Parser)]
@@ -13,14 +28,122 @@ use super::{
     version = "1.0"
 )]
 pub struct Cli {
+    #[command(flatten)]
+    pub config: ConfigArgs,
+    #[command(flatten)]
+    pub log: LogArgs,
     #[command(subcommand)]
     pub command: Commands,
 }
 
+#[derive(Debug, Args)]
+pub struct LogArgs {
+    #[arg(long, short = 'v', action = ArgAction::Count, global = true)]
+    /// Log more details (-v debug, -vv trace)
+    pub verbose: u8,
+    #[arg(long, short = 'q', action = ArgAction::Count, global = true)]
+    /// Log less details (-q warnings, -qq errors only)
+    pub quiet: u8,
+    #[arg(long, value_enum, default_value_t, global = true)]
+    /// Log output format (text, json)
+    pub log_format: LogFormat,
+}
+
+#[derive(Debug, Args)]
+pub struct ConfigArgs {
+    #[arg(long, global = true)]
+    /// (optional) Config file path (defaults to ~/.config/sport-cli/config.toml)
+    pub config: Option<PathBuf>,
+    #[arg(long, global = true)]
+    /// (optional) Config file profile to use
+    pub profile: Option<String>,
+    #[arg(long, global = true)]
+    /// (optional) Provider api key, or several separated by commas
+    pub api_key: Option<String>,
+    #[arg(long, global = true)]
+    /// (optional) File containing the provider api keys, one per line
+    pub api_key_file: Option<PathBuf>,
+    #[arg(long, global = true)]
+    /// (optional) Provider access level (trial, production)
+    pub access_level: Option<String>,
+    #[arg(long, global = true)]
+    /// (optional) Provider language code, such as en
+    pub language_code: Option<String>,
+    #[arg(long, global = true)]
+    /// (optional) Provider response format (json, xml)
+    pub format: Option<Format>,
+    #[arg(long, global = true)]
+    /// (optional) Provider requests allowed per api key and month
+    pub monthly_limit: Option<u64>,
+    #[arg(long, global = true)]
+    /// (optional) Provider requests sent per second at most, such as 1 for trial keys (defaults to no limit)
+    pub rate_limit: Option<f64>,
+    #[arg(long, global = true)]
+    /// (optional) Provider requests in flight at most, adapted to the provider responses (defaults to 4)
+    pub concurrency: Option<usize>,
+}
+
+impl From<&ConfigArgs> for ConfigOptions {
+    fn from(args: &ConfigArgs) -> Self {
+        ConfigOptions {
+            path: args.config.clone(),
+            profile: args.profile.clone(),
+            overrides: ConfigLayer {
+                api_key: args.api_key.clone(),
+                api_keys: None,
+                api_key_file: args.api_key_file.clone(),
+                access_level: args.access_level.clone(),
+                language_code: args.language_code.clone(),
+                format: args.format,
+                monthly_limit: args.monthly_limit,
+                rate_limit: args.rate_limit,
+                concurrency: args.concurrency,
+            },
+        }
+    }
+}
+
+/// The competition season to scrape or query.
+#[derive(Debug, Args)]
+pub struct QueryArgs {
+    #[arg(long, value_enum, short = 'S')]
+    /// Sport to analyze (football)
+    pub sport: SportArg,
+    #[arg(long, short = 'E')]
+    /// The event name, such as "Premier's League"
+    pub event: String,
+    #[arg(long, short = 'L')]
+    /// The location of the event, such as England, International, etc...
+    pub location: String,
+    #[arg(long, value_enum, short = 'G')]
+    /// Analyze data by gender (male, female)
+    pub gender: GenderArg,
+    #[arg(long)]
+    /// The start date of the season in YYYY-MM-DD
+    pub season_start: NaiveDate,
+    #[arg(long)]
+    /// The end date of the season in YYYY-MM-DD
+    pub season_end: NaiveDate,
+}
+
+impl From<&QueryArgs> for Query {
+    fn from(args: &QueryArgs) -> Self {
+        Query {
+            event: args.event.to_string(),
+            location: args.location.to_string(),
+            gender: args.gender.value.clone(),
+            season_start: args.season_start,
+            season_end: args.season_end,
+        }
+    }
+}
+
 #[derive(Debug, Subcommand)]
 pub enum Commands {
     /// Perform analytics queries on sports data.
     Analytics(AnalyticsArgs),
+    /// Perform an analytics query written in the query language.
+    Query(TextQueryArgs),
     /// Perform queries on sport competitions data.
     Competitions(CompetitionsArgs),
     /// Perform queries on sport seasons data, given competition.
@@ -29,4 +152,12 @@ pub enum Commands {
     Teams(TeamsArgs),
     /// Perform queries on sport players data, given competition and season.
     Players(PlayersArgs),
+    /// Diagnose the sport data provider integration.
+    Doctor(DoctorArgs),
+    /// Report the provider requests sent this month against the monthly limit.
+    Quota(QuotaArgs),
+    /// Scrape a competition season into the local store, for offline analytics.
+    Sync(SyncArgs),
+    /// Save, load and list frozen datasets of the local store.
+    Snapshot(SnapshotArgs),
 }
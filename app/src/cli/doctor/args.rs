@@ -0,0 +1,27 @@
+use clap::{Args, Subcommand};
+use engine::id::{CompetitionId, CompetitorId, SeasonId};
+
+#[derive(Debug, Args)]
+pub struct DoctorArgs {
+    #[command(subcommand)]
+    pub command: DoctorCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DoctorCommands {
+    /// Report provider fields that drifted from the models.
+    Schema(SchemaArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct SchemaArgs {
+    #[arg(long)]
+    /// (optional) Competition to check, such as sr:competition:17 (defaults to the first one)
+    pub competition: Option<CompetitionId>,
+    #[arg(long)]
+    /// (optional) Season to check, such as sr:season:105353 (defaults to the latest one)
+    pub season: Option<SeasonId>,
+    #[arg(long)]
+    /// (optional) Competitor to check, such as sr:competitor:44 (defaults to the first one)
+    pub competitor: Option<CompetitorId>,
+}
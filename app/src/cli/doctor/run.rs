@@ -0,0 +1,78 @@
+use super::args::SchemaArgs;
+
+use anyhow::{anyhow, Result};
+use sport_radar::{
+    client::SportRadarClient,
+    config::{Format, SportRadarConfig},
+    drift::SchemaDrift,
+};
+
+pub async fn schema(config: SportRadarConfig, args: &SchemaArgs) -> Result<()> {
+    // Checked before any request, so no quota is spent on responses that cannot be compared
+    if *config.format() != Format::Json {
+        return Err(anyhow!(
+            "doctor schema only supports json responses, use --format json or FORMAT=json"
+        ));
+    }
+    let client = SportRadarClient::new(config).with_drift_detection();
+
+    // Walk every endpoint once, following the ids found along the way
+    let competitions = client.get_competitions().await?;
+    let competition_id = match &args.competition {
+        Some(competition_id) => competition_id.clone(),
+        None => competitions
+            .competitions
+            .first()
+            .map(|competition| competition.id.clone())
+            .ok_or_else(|| anyhow!("No competitions found"))?,
+    };
+
+    let seasons = client.get_competition_seasons(&competition_id).await?;
+    let season_id = match &args.season {
+        Some(season_id) => season_id.clone(),
+        None => seasons
+            .seasons
+            .last()
+            .map(|season| season.id.clone())
+            .ok_or_else(|| anyhow!("No seasons found for {}", competition_id))?,
+    };
+
+    let competitors = client.get_season_competitors(&season_id).await?;
+    let competitor_id = match &args.competitor {
+        Some(competitor_id) => competitor_id.clone(),
+        None => competitors
+            .season_competitors
+            .first()
+            .map(|competitor| competitor.id.clone())
+            .ok_or_else(|| anyhow!("No competitors found for {}", season_id))?,
+    };
+
+    client
+        .get_seasonal_competitor_statistics(&season_id, &competitor_id)
+        .await?;
+
+    print_report(&client.schema_drift().unwrap_or_default());
+
+    Ok(())
+}
+
+fn print_report(drift: &SchemaDrift) {
+    if drift.is_empty() {
+        println!("No schema drift detected");
+        return;
+    }
+
+    for (model, model_drift) in &drift.models {
+        if model_drift.is_empty() {
+            println!("{}: ok", model);
+            continue;
+        }
+        println!("{}:", model);
+        for field in &model_drift.unknown {
+            println!("  unknown: {}", field);
+        }
+        for field in &model_drift.missing {
+            println!("  missing: {}", field);
+        }
+    }
+}
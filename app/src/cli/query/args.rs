@@ -0,0 +1,24 @@
+use clap::Args;
+use engine::engine::Query;
+
+#[derive(Debug, Args)]
+pub struct TextQueryArgs {
+    /// The query, such as "top 10 players by goals+assists in 'Premier League' England 2023/24
+    /// where matches >= 10"
+    pub query: Query,
+    #[arg(long)]
+    /// (optional) Print the provider calls the query would make, without making them
+    pub dry_run: bool,
+    #[arg(long)]
+    /// (optional) Fail instead of answering when some competitors could not be scraped
+    pub strict: bool,
+    #[arg(long)]
+    /// (optional) Fetch the competitors that failed once more before answering
+    pub retry_failed: bool,
+    #[arg(long)]
+    /// (optional) Discard the progress saved by a previous run of the query and start over
+    pub restart: bool,
+    #[arg(long)]
+    /// (optional) Answer from the data synced by `sport-cli sync`, without calling the provider
+    pub offline: bool,
+}
@@ -0,0 +1,9 @@
+use chrono::NaiveDate;
+use clap::Args;
+
+#[derive(Debug, Args)]
+pub struct QuotaArgs {
+    #[arg(long)]
+    /// (optional) Report the month of this date in YYYY-MM-DD (defaults to today)
+    pub date: Option<NaiveDate>,
+}
@@ -0,0 +1,55 @@
+use super::args::QuotaArgs;
+
+use anyhow::Result;
+use chrono::Utc;
+use sport_radar::{
+    config::SportRadarConfig,
+    keys::{fingerprint, mask},
+    ledger::{Ledger, QuotaReport},
+};
+
+pub fn run(config: &SportRadarConfig, ledger: &Ledger, args: &QuotaArgs) -> Result<()> {
+    let day = args.date.unwrap_or_else(|| Utc::now().date_naive());
+    let report = QuotaReport::new(&ledger.records()?, day);
+
+    println!(
+        "Quota for {} (ledger: {})",
+        day.format("%Y-%m"),
+        ledger.path().display()
+    );
+    for key in config.api_keys() {
+        let quota = report
+            .keys
+            .get(&fingerprint(key))
+            .cloned()
+            .unwrap_or_default();
+        let key = mask(key);
+        match config.monthly_limit() {
+            Some(limit) => {
+                let percent = quota.month as f64 * 100.0 / limit.max(1) as f64;
+                println!(
+                    "{}: {}/{} requests this month ({:.0}%), {} today",
+                    key, quota.month, limit, percent, quota.today
+                );
+                if quota.month >= limit {
+                    println!("  warning: monthly limit reached");
+                }
+            }
+            None => println!(
+                "{}: {} requests this month, {} today",
+                key, quota.month, quota.today
+            ),
+        }
+        for (endpoint, usage) in &quota.endpoints {
+            println!(
+                "  {}: {} requests, {} failed, {}ms average latency",
+                endpoint,
+                usage.requests,
+                usage.failures,
+                usage.average_latency_ms()
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use crate::cli::args::QueryArgs;
+
+use clap::{Args, Subcommand};
+
+#[derive(Debug, Args)]
+pub struct SnapshotArgs {
+    #[command(subcommand)]
+    pub command: SnapshotCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SnapshotCommands {
+    /// Freeze the synced data of a competition season into a compressed snapshot file.
+    Save(SaveArgs),
+    /// Import a snapshot into the local store, to query it with `analytics --offline`.
+    Load(LoadArgs),
+    /// List the saved snapshots.
+    List,
+}
+
+#[derive(Debug, Args)]
+pub struct SaveArgs {
+    /// The snapshot name, such as pl-2023, or a path to the snapshot file
+    pub name: String,
+    #[command(flatten)]
+    pub query: QueryArgs,
+    #[arg(long)]
+    /// (optional) Overwrite the snapshot if it exists
+    pub force: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct LoadArgs {
+    /// The snapshot name, as listed by `snapshot list`, or a path to the snapshot file
+    pub name: String,
+}
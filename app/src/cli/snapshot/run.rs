@@ -0,0 +1,89 @@
+use super::args::{LoadArgs, SaveArgs};
+use crate::{
+    scrapper::Query,
+    snapshot::{self, Snapshot},
+    store::Store,
+};
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use std::fs;
+use std::path::Path;
+use tracing::warn;
+
+pub fn save(store: &Store, dir: &Path, args: &SaveArgs) -> Result<()> {
+    let query: Query = (&args.query).into();
+    let path = snapshot::resolve(dir, &args.name);
+    if path.exists() && !args.force {
+        return Err(anyhow!(
+            "Snapshot {} already exists, pass --force to overwrite it",
+            path.display()
+        ));
+    }
+
+    let Some((db, synced_at)) = store.load(&query)? else {
+        return Err(anyhow!(
+            "No synced data for {} ({}) {} to {}, run `sport-cli sync` first",
+            query.event,
+            query.location,
+            query.season_start,
+            query.season_end
+        ));
+    };
+    let fetched_at = synced_at.unwrap_or_else(|| {
+        warn!("The synced data has no fetch time, using the current time");
+        Utc::now()
+    });
+
+    Snapshot::new("sportradar", fetched_at, query, db).write(&path)?;
+    println!("Saved snapshot {}", path.display());
+    Ok(())
+}
+
+pub fn load(store: &Store, dir: &Path, args: &LoadArgs) -> Result<()> {
+    let path = snapshot::resolve(dir, &args.name);
+    let snapshot = Snapshot::read(&path)?;
+    store.save(&snapshot.db, snapshot.header.fetched_at)?;
+
+    let scope = &snapshot.header.scope;
+    println!(
+        "Loaded snapshot {} into {}: {} ({}) {} to {}, fetched from {} at {}",
+        path.display(),
+        store.path().display(),
+        scope.event,
+        scope.location,
+        scope.season_start,
+        scope.season_end,
+        snapshot.header.provider,
+        snapshot.header.fetched_at
+    );
+    Ok(())
+}
+
+pub fn list(dir: &Path) -> Result<()> {
+    let snapshots = snapshot::list(dir)?;
+    if snapshots.is_empty() {
+        println!("No snapshots in {}", dir.display());
+    }
+    for (path, header) in snapshots {
+        let name = path.file_stem().unwrap_or_default().to_string_lossy();
+        let size = fs::metadata(&path).map(|m| m.len()).unwrap_or_default();
+        match header {
+            Ok(header) => println!(
+                "{}: {} ({}, {:?}) {} to {}, fetched from {} at {}, format v{}, {} KB",
+                name,
+                header.scope.event,
+                header.scope.location,
+                header.scope.gender,
+                header.scope.season_start,
+                header.scope.season_end,
+                header.provider,
+                header.fetched_at,
+                header.format_version,
+                size.div_ceil(1024)
+            ),
+            Err(e) => println!("{}: unreadable, {}", name, e),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,18 @@
+use crate::cli::args::QueryArgs;
+
+use clap::Args;
+
+#[derive(Debug, Args)]
+pub struct SyncArgs {
+    #[command(flatten)]
+    pub query: QueryArgs,
+    #[arg(long)]
+    /// (optional) Fetch the competitors that failed once more before saving
+    pub retry_failed: bool,
+    #[arg(long)]
+    /// (optional) Discard the progress saved by a previous run of the query and start over
+    pub restart: bool,
+    #[arg(long)]
+    /// (optional) Save the competitors scraped even when some failed, leaving the others out
+    pub allow_partial: bool,
+}
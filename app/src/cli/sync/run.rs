@@ -0,0 +1,70 @@
+use crate::{
+    db::Db,
+    scrapper::{Query, Scrapper},
+    store::Store,
+};
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use engine::repo::in_memo::InMemoRepository;
+use sport_radar::client::SportRadarClient;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// How to sync a competition season, from the cli flags.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SyncOptions {
+    pub retry_failed: bool,
+    pub restart: bool,
+    pub allow_partial: bool,
+}
+
+pub async fn run(
+    sport_data_source: SportRadarClient,
+    checkpoints: PathBuf,
+    store: &Store,
+    query: Query,
+    options: SyncOptions,
+) -> Result<()> {
+    let db = Arc::new(Mutex::new(Db::new()));
+    let scrapper = Scrapper::default().with_checkpoints(checkpoints);
+    if options.restart {
+        scrapper.restart(&query)?;
+    }
+
+    let synced_at = Utc::now();
+    let report = scrapper
+        .execute(
+            Arc::new(sport_data_source),
+            &query,
+            Arc::clone(&db),
+            options.retry_failed,
+        )
+        .await?;
+    if !report.is_complete() {
+        eprintln!("WARNING: {}", report);
+        // Offline answers would silently miss the players of the failed competitors
+        if !options.allow_partial {
+            return Err(anyhow!(
+                "{} competitor(s) could not be scraped, nothing was saved: \
+                 rerun to resume them, or pass --allow-partial to save the others",
+                report.failed.len()
+            ));
+        }
+    }
+
+    let db = db.lock().unwrap();
+    store.save(&db, synced_at)?;
+    println!(
+        "Synced {} ({}) {} to {} into {}: {} team(s), {} player(s) stored",
+        query.event,
+        query.location,
+        query.season_start,
+        query.season_end,
+        store.path().display(),
+        db.teams.all().len(),
+        db.players.all().len()
+    );
+
+    Ok(())
+}
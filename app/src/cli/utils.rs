@@ -1,8 +1,8 @@
 use std::str::FromStr;
 
 use engine::{
-    engine::{Dimension, Sort},
-    repo::model::{Gender, Metric},
+    engine::{Dimension, MetricKind, Sort},
+    repo::model::Gender,
 };
 use serde_derive::{Deserialize, Serialize};
 
@@ -47,7 +47,7 @@ impl FromStr for DimensionArg {
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct MetricArg {
-    pub value: Metric,
+    pub value: MetricKind,
 }
 impl FromStr for MetricArg {
     type Err = String;
@@ -55,12 +55,12 @@ impl FromStr for MetricArg {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
             "score" => Ok(MetricArg {
-                value: Metric::GoalsScored { value: 0 },
+                value: MetricKind::GoalsScored,
             }),
             "assist" => Ok(MetricArg {
-                value: Metric::Assists { value: 0 },
+                value: MetricKind::Assists,
             }),
-            _ => Err(format!("Invalid metric: {}", s)),
+            _ => Ok(MetricArg { value: s.parse()? }),
         }
     }
 }
@@ -85,18 +85,37 @@ impl FromStr for GenderArg {
     }
 }
 
+/// A sort key, such as `goals_scored:desc`. A bare order (`asc`, `desc`) sorts by the
+/// first metric, and a bare metric sorts in descending order.
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct SortArg {
+    pub metric: Option<MetricKind>,
     pub value: Sort,
 }
 impl FromStr for SortArg {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
-            "asc" => Ok(SortArg { value: Sort::Asc }),
-            "desc" => Ok(SortArg { value: Sort::Desc }),
-            _ => Err(format!("Invalid sort: {}", s)),
+        let order = |order: &str| match order.to_lowercase().as_str() {
+            "asc" => Ok(Sort::Asc),
+            "desc" => Ok(Sort::Desc),
+            _ => Err(format!("Invalid sort: {}", order)),
+        };
+        match s.split_once(':') {
+            Some((metric, value)) => Ok(SortArg {
+                metric: Some(metric.parse::<MetricArg>()?.value),
+                value: order(value)?,
+            }),
+            None => match order(s) {
+                Ok(value) => Ok(SortArg {
+                    metric: None,
+                    value,
+                }),
+                Err(_) => Ok(SortArg {
+                    metric: Some(s.parse::<MetricArg>()?.value),
+                    value: Sort::Desc,
+                }),
+            },
         }
     }
 }
@@ -1,14 +1,17 @@
 use engine::repo::{
     competitions::CompetitionsRepo, player_stats::PlayerStatsRepo, players::PlayersRepo,
-    teams::TeamsRepo,
+    team_stats::TeamStatsRepo, teams::TeamsRepo,
 };
+use serde_derive::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Db {
     pub players: PlayersRepo,
     pub competitions: CompetitionsRepo,
     pub teams: TeamsRepo,
     pub players_stats: PlayerStatsRepo,
+    #[serde(default)]
+    pub teams_stats: TeamStatsRepo,
 }
 
 impl Db {
@@ -0,0 +1,49 @@
+pub mod checkpoint;
+pub mod db;
+
+pub mod cli {
+    pub mod analytics {
+        pub mod args;
+        pub mod run;
+    }
+    pub mod competitions {
+        pub mod args;
+    }
+    pub mod doctor {
+        pub mod args;
+        pub mod run;
+    }
+    pub mod players {
+        pub mod args;
+    }
+    pub mod query {
+        pub mod args;
+    }
+    pub mod quota {
+        pub mod args;
+        pub mod run;
+    }
+    pub mod seasons {
+        pub mod args;
+    }
+    pub mod snapshot {
+        pub mod args;
+        pub mod run;
+    }
+    pub mod sync {
+        pub mod args;
+        pub mod run;
+    }
+    pub mod teams {
+        pub mod args;
+    }
+    pub mod args;
+    pub mod utils;
+}
+
+pub mod logging;
+pub mod plan;
+pub mod report;
+pub mod scrapper;
+pub mod snapshot;
+pub mod store;
@@ -1,50 +1,88 @@
-mod db;
-
-mod cli {
-    pub mod analytics {
-        pub mod args;
-        pub mod run;
-    }
-    pub mod competitions {
-        pub mod args;
-    }
-    pub mod players {
-        pub mod args;
-    }
-    pub mod seasons {
-        pub mod args;
-    }
-    pub mod teams {
-        pub mod args;
-    }
-    pub mod args;
-    pub mod utils;
-}
-
-use cli::args::*;
-
-mod scrapper;
-use scrapper::Query;
-
 use anyhow::Result;
+use app::cli::{self, args::*, doctor::args::DoctorCommands, snapshot::args::SnapshotCommands};
+use app::logging;
+use app::store::Store;
 use clap::Parser;
+use sport_radar::{
+    client::SportRadarClient,
+    config::{default_data_dir, SportRadarConfig},
+    ledger::Ledger,
+};
+use std::{path::PathBuf, sync::Arc};
+use tracing::debug;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
+    logging::init(
+        logging::level(cli.log.verbose, cli.log.quiet),
+        cli.log.log_format,
+    );
+    // Loaded by the commands calling the provider, so offline ones need no api key
+    let config = || SportRadarConfig::load(&(&cli.config).into());
+    let data_dir = default_data_dir().unwrap_or_else(|| PathBuf::from("."));
+    let ledger = Arc::new(Ledger::new(data_dir.join("ledger.jsonl")));
+    let checkpoints = data_dir.join("checkpoints");
+    let store = Store::new(data_dir.join("store.sqlite"));
+    let snapshots = data_dir.join("snapshots");
 
     match &cli.command {
         Commands::Analytics(args) => {
-            println!("Analytics Query: {:?}", args);
-            let query = Query {
-                event: args.event.to_string(),
-                location: args.location.to_string(),
-                gender: args.gender.value.clone(),
-                season_start: args.season_start,
-                season_end: args.season_end,
+            debug!(?args, "Analytics Query");
+            let query = args.into();
+            let aggregation = args.aggregation();
+            if args.offline {
+                return cli::analytics::run::run_offline(&store, query, aggregation);
+            }
+            let options = cli::analytics::run::RunOptions {
+                dry_run: args.dry_run,
+                strict: args.strict,
+                retry_failed: args.retry_failed,
+                restart: args.restart,
+            };
+            let client = SportRadarClient::new(config()?).with_ledger(ledger);
+            cli::analytics::run::run(client, checkpoints, &store, query, aggregation, options).await
+        }
+        Commands::Query(args) => {
+            debug!(query = %args.query, "Text Query");
+            let query = args.query.clone();
+            if args.offline {
+                return cli::analytics::run::run_offline(&store, query, None);
+            }
+            let options = cli::analytics::run::RunOptions {
+                dry_run: args.dry_run,
+                strict: args.strict,
+                retry_failed: args.retry_failed,
+                restart: args.restart,
+            };
+            let client = SportRadarClient::new(config()?).with_ledger(ledger);
+            cli::analytics::run::run(client, checkpoints, &store, query, None, options).await
+        }
+        Commands::Doctor(args) => match &args.command {
+            DoctorCommands::Schema(args) => cli::doctor::run::schema(config()?, args).await,
+        },
+        Commands::Quota(args) => cli::quota::run::run(&config()?, &ledger, args),
+        Commands::Sync(args) => {
+            debug!(?args, "Sync Query");
+            let options = cli::sync::run::SyncOptions {
+                retry_failed: args.retry_failed,
+                restart: args.restart,
+                allow_partial: args.allow_partial,
             };
-            cli::analytics::run::run(query).await
+            cli::sync::run::run(
+                SportRadarClient::new(config()?).with_ledger(ledger),
+                checkpoints,
+                &store,
+                (&args.query).into(),
+                options,
+            )
+            .await
         }
+        Commands::Snapshot(args) => match &args.command {
+            SnapshotCommands::Save(args) => cli::snapshot::run::save(&store, &snapshots, args),
+            SnapshotCommands::Load(args) => cli::snapshot::run::load(&store, &snapshots, args),
+            SnapshotCommands::List => cli::snapshot::run::list(&snapshots),
+        },
         _ => todo!(),
     }
 }
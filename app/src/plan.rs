@@ -0,0 +1,149 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::scrapper::Query;
+
+/// Season competitors assumed when no season of the competition was scraped yet,
+/// as in most leagues.
+pub const ASSUMED_COMPETITORS: u64 = 20;
+
+/// How many competitors a season is expected to have, before fetching them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompetitorsEstimate {
+    pub competitors: u64,
+    /// The season scraped before it comes from, or `None` if it is `ASSUMED_COMPETITORS`.
+    pub from: Option<Query>,
+}
+
+impl Default for CompetitorsEstimate {
+    fn default() -> Self {
+        CompetitorsEstimate {
+            competitors: ASSUMED_COMPETITORS,
+            from: None,
+        }
+    }
+}
+
+/// A provider endpoint the scrapper calls while resolving a query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedCall {
+    pub description: &'static str,
+    pub endpoint: &'static str,
+    /// How many calls, or `None` for one per season competitor.
+    pub calls: Option<u64>,
+    /// How many of those calls are served from local data.
+    pub cached: u64,
+}
+
+impl PlannedCall {
+    pub fn calls_or(&self, competitors: &CompetitorsEstimate) -> u64 {
+        self.calls.unwrap_or(competitors.competitors)
+    }
+
+    pub fn network_calls(&self, competitors: &CompetitorsEstimate) -> u64 {
+        self.calls_or(competitors).saturating_sub(self.cached)
+    }
+}
+
+/// The resolution plan the scrapper would execute for a query, as shown by `--dry-run`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plan {
+    pub query: Query,
+    pub calls: Vec<PlannedCall>,
+    /// For the calls made once per season competitor, while their number is not known.
+    pub competitors: CompetitorsEstimate,
+    /// Requests per second sent at most, or 0 for no limit.
+    pub rate_limit: f64,
+    /// Requests left this month, if a monthly limit is configured.
+    pub remaining_quota: Option<u64>,
+}
+
+impl Plan {
+    /// Requests sent to the provider, i.e. consumed quota.
+    pub fn network_calls(&self) -> u64 {
+        self.calls
+            .iter()
+            .map(|call| call.network_calls(&self.competitors))
+            .sum()
+    }
+
+    /// Wall-clock time spent waiting on the rate limit, if any.
+    pub fn estimated_duration(&self) -> Option<Duration> {
+        if self.rate_limit <= 0.0 {
+            return None;
+        }
+        // Saturates for rate limits too low to be waited out anyway
+        let seconds = self.network_calls() as f64 / self.rate_limit;
+        Some(Duration::try_from_secs_f64(seconds).unwrap_or(Duration::MAX))
+    }
+
+    fn assumes_competitors(&self) -> bool {
+        self.calls.iter().any(|call| call.calls.is_none())
+    }
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "Plan for {} ({}, {:?}) {} to {}:",
+            self.query.event,
+            self.query.location,
+            self.query.gender,
+            self.query.season_start,
+            self.query.season_end
+        )?;
+        for (step, call) in self.calls.iter().enumerate() {
+            let calls = match call.calls {
+                Some(calls) => format!("{} call(s)", calls),
+                None => "1 call per season competitor".to_string(),
+            };
+            let source = match (call.cached, call.network_calls(&self.competitors)) {
+                (0, _) => "network".to_string(),
+                (_, 0) => "cache".to_string(),
+                (cached, _) => format!("{} from cache, the rest from network", cached),
+            };
+            writeln!(
+                f,
+                "  {}. {} [{}]: {} ({})",
+                step + 1,
+                call.description,
+                call.endpoint,
+                calls,
+                source
+            )?;
+        }
+
+        write!(f, "Estimated quota: {} requests", self.network_calls())?;
+        if self.assumes_competitors() {
+            match &self.competitors.from {
+                Some(season) => write!(
+                    f,
+                    " (assuming {} competitors, as from {} to {})",
+                    self.competitors.competitors, season.season_start, season.season_end
+                )?,
+                None => write!(
+                    f,
+                    " (assuming {} competitors)",
+                    self.competitors.competitors
+                )?,
+            }
+        }
+        match self.remaining_quota {
+            Some(remaining) if remaining < self.network_calls() => {
+                writeln!(f, ", exceeds the {} remaining this month", remaining)?
+            }
+            Some(remaining) => writeln!(f, ", {} remaining this month", remaining)?,
+            None => writeln!(f)?,
+        }
+        match self.estimated_duration() {
+            Some(duration) => write!(
+                f,
+                "Estimated time: {}s at {} requests/s",
+                duration.as_secs(),
+                self.rate_limit
+            ),
+            None => write!(f, "Estimated time: not rate limited (see --rate-limit)"),
+        }
+    }
+}
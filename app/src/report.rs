@@ -0,0 +1,83 @@
+use std::fmt;
+
+use engine::id::CompetitorId;
+
+/// A competitor whose statistics were fetched and stored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScrapedCompetitor {
+    pub id: CompetitorId,
+    pub name: String,
+}
+
+/// A competitor whose statistics are missing from the results.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailedCompetitor {
+    pub id: CompetitorId,
+    pub name: String,
+    pub reason: String,
+}
+
+/// The outcome of a scrape, per season competitor.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunReport {
+    pub succeeded: Vec<ScrapedCompetitor>,
+    pub failed: Vec<FailedCompetitor>,
+    /// Competitors done by a previous run, replayed from its checkpoint.
+    pub resumed: usize,
+    /// Failed competitors fetched once more under `--retry-failed`.
+    pub retried: usize,
+}
+
+impl RunReport {
+    pub fn competitors(&self) -> usize {
+        self.succeeded.len() + self.failed.len()
+    }
+
+    /// Whether every competitor made it into the results.
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    pub fn merge(&mut self, other: RunReport) {
+        self.succeeded.extend(other.succeeded);
+        self.failed.extend(other.failed);
+        self.resumed += other.resumed;
+        self.retried += other.retried;
+    }
+
+    /// Replaces the failures with the outcome of refetching them.
+    pub fn merge_retry(&mut self, retry: RunReport) {
+        self.retried += retry.competitors();
+        self.succeeded.extend(retry.succeeded);
+        self.failed = retry.failed;
+    }
+}
+
+impl fmt::Display for RunReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Scraped {}/{} competitors",
+            self.succeeded.len(),
+            self.competitors()
+        )?;
+        if self.resumed > 0 {
+            write!(f, " ({} resumed from checkpoint)", self.resumed)?;
+        }
+        if self.retried > 0 {
+            write!(f, " ({} retried)", self.retried)?;
+        }
+        if self.is_complete() {
+            return Ok(());
+        }
+
+        writeln!(f, ", results are INCOMPLETE. Missing competitors:")?;
+        for (i, failed) in self.failed.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "  - {} ({}): {}", failed.name, failed.id, failed.reason)?;
+        }
+        Ok(())
+    }
+}
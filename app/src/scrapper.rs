@@ -1,21 +1,31 @@
+use super::checkpoint::{Checkpoint, SeasonCheckpoint};
 use super::db::Db;
+use super::plan::{CompetitorsEstimate, Plan, PlannedCall};
+use super::report::{FailedCompetitor, RunReport, ScrapedCompetitor};
+use super::store::Store;
 use anyhow::{anyhow, Result};
 use chrono::NaiveDate;
+use engine::engine::Query as EngineQuery;
+use engine::id::{CompetitionId, CompetitorId, SeasonId};
 use engine::repo::{
     in_memo::InMemoRepository,
     model::{
         Competition as EngineCompetition, Gender, Metric as RepoMetric, Player, PlayerStats, Team,
+        TeamMetric, TeamStats,
     },
 };
 use serde_derive::{Deserialize, Serialize};
 use sport_radar::{
     client::SportRadarClient,
     model::{
-        CompetitionGender, CompetitionsResponse, Competitor, CompetitorsResponse,
-        PlayerStatisticsResponse, Season, SeasonsResponse,
+        CompetitionGender, CompetitionsResponse, Competitor, CompetitorStatistics,
+        CompetitorsResponse, PlayerStatistics, PlayerStatisticsResponse, Season, SeasonsResponse,
     },
 };
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
+use tokio::task::JoinSet;
+use tracing::{error, info, info_span, warn, Instrument};
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Query {
@@ -26,49 +36,355 @@ pub struct Query {
     pub season_end: NaiveDate,
 }
 
-#[derive(Debug, Clone)]
-pub struct Scrapper;
+impl Query {
+    /// Whether the competition is the one queried, whatever its season.
+    pub fn is_competition(&self, competition: &EngineCompetition) -> bool {
+        competition.name == self.event
+            && competition.location == self.location
+            && competition.gender == self.gender
+    }
+
+    /// The season the queried dates select, given the seasons and their dates: the season
+    /// with exactly these dates, or else the only one lying within them, such as 2023-08-11
+    /// to 2024-05-19 within the 2023/24 window of 2023-07-01 to 2024-06-30.
+    /// Fails when the dates cover several seasons, rather than picking one of them.
+    pub fn select_season<T>(
+        &self,
+        seasons: impl IntoIterator<Item = T>,
+        dates: impl Fn(&T) -> (NaiveDate, NaiveDate),
+    ) -> Result<Option<T>> {
+        let mut covered = vec![];
+        for season in seasons {
+            let (start, end) = dates(&season);
+            if (start, end) == (self.season_start, self.season_end) {
+                return Ok(Some(season));
+            }
+            if self.season_start <= start && end <= self.season_end {
+                covered.push((start, end, season));
+            }
+        }
+        if covered.len() > 1 {
+            let seasons: Vec<String> = covered
+                .iter()
+                .map(|(start, end, _)| format!("{} to {}", start, end))
+                .collect();
+            return Err(anyhow!(
+                "{} to {} covers several seasons ({}), narrow the dates to one of them",
+                self.season_start,
+                self.season_end,
+                seasons.join(", ")
+            ));
+        }
+        Ok(covered.pop().map(|(_, _, season)| season))
+    }
+}
+
+/// The competition season an analytics query is about.
+impl From<&EngineQuery> for Query {
+    fn from(query: &EngineQuery) -> Self {
+        Query {
+            event: query.event.to_string(),
+            location: query.location.to_string(),
+            gender: query.gender.clone(),
+            season_start: query.season_start,
+            season_end: query.season_end,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Scrapper {
+    checkpoints: Option<PathBuf>,
+    store: Option<Store>,
+}
 
 impl Scrapper {
+    /// Saves the progress of every scrape in the given directory, resuming it when rerun.
+    pub fn with_checkpoints(mut self, dir: PathBuf) -> Self {
+        self.checkpoints = Some(dir);
+        self
+    }
+
+    /// Plans scrapes from the seasons synced in the given store, as to estimate their size.
+    pub fn with_store(mut self, store: Store) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Discards the saved progress of the query, so the next scrape starts over.
+    pub fn restart(&self, query: &Query) -> Result<()> {
+        match &self.checkpoints {
+            Some(dir) => Checkpoint::discard(dir, query),
+            None => Ok(()),
+        }
+    }
+
+    fn checkpoint(&self, query: &Query) -> Result<Option<Checkpoint>> {
+        self.checkpoints
+            .as_ref()
+            .map(|dir| Checkpoint::load(dir, query))
+            .transpose()
+    }
+
+    /// The provider calls `execute` would make for the query, without making them.
+    pub fn plan(&self, sport_data_source: &SportRadarClient, query: &Query) -> Result<Plan> {
+        let checkpoint = self.checkpoint(query)?;
+        let season = checkpoint.as_ref().and_then(|c| c.season.as_ref());
+        let lookups_cached = if season.is_some() { 1 } else { 0 };
+        let calls = vec![
+            PlannedCall {
+                description: "Competitions lookup",
+                endpoint: "competitions",
+                calls: Some(1),
+                cached: lookups_cached,
+            },
+            PlannedCall {
+                description: "Seasons lookup",
+                endpoint: "competition_seasons",
+                calls: Some(1),
+                cached: lookups_cached,
+            },
+            PlannedCall {
+                description: "Competitors lookup",
+                endpoint: "season_competitors",
+                calls: Some(1),
+                cached: lookups_cached,
+            },
+            PlannedCall {
+                description: "Competitor statistics",
+                endpoint: "seasonal_competitor_statistics",
+                calls: season.map(|season| season.competitors.len() as u64),
+                cached: checkpoint.as_ref().map_or(0, |c| c.done.len() as u64),
+            },
+        ];
+
+        Ok(Plan {
+            query: query.clone(),
+            calls,
+            competitors: self.estimate_competitors(query)?,
+            rate_limit: sport_data_source.config().rate_limit(),
+            remaining_quota: sport_data_source.remaining_quota()?,
+        })
+    }
+
+    /// The competitors of the latest season of the competition known locally, if any,
+    /// from the checkpoints and the store, as its seasons tend to have as many.
+    fn estimate_competitors(&self, query: &Query) -> Result<CompetitorsEstimate> {
+        let mut seasons = vec![];
+        if let Some(dir) = &self.checkpoints {
+            seasons.extend(
+                Checkpoint::list(dir)?
+                    .into_iter()
+                    .filter_map(|checkpoint| checkpoint.season)
+                    .filter(|season| query.is_competition(&season.competition))
+                    .map(|season| (season.competition, season.competitors.len())),
+            );
+        }
+        if let Some(store) = &self.store {
+            seasons.extend(store.seasons(query)?);
+        }
+        let latest = seasons
+            .into_iter()
+            .filter(|(_, competitors)| *competitors > 0)
+            .max_by_key(|(season, _)| season.season_start);
+        Ok(match latest {
+            Some((season, competitors)) => CompetitorsEstimate {
+                competitors: competitors as u64,
+                from: Some(Query {
+                    season_start: season.season_start,
+                    season_end: season.season_end,
+                    ..query.clone()
+                }),
+            },
+            None => CompetitorsEstimate::default(),
+        })
+    }
+
+    /// Scrapes the query data into the db, reporting the competitors whose statistics failed.
+    /// With `retry_failed`, the failed competitors are fetched once more before reporting.
     pub async fn execute(
         &self,
         sport_data_source: Arc<SportRadarClient>,
         query: &Query,
         db: Arc<Mutex<Db>>,
-    ) -> Result<()> {
+        retry_failed: bool,
+    ) -> Result<RunReport> {
+        let mut checkpoint = self.checkpoint(query)?;
+
+        // Steps 1 to 5: Resolve the season competitors, unless a previous run did
+        let season = match checkpoint.as_ref().and_then(|c| c.season.clone()) {
+            Some(season) => {
+                info!(
+                    checkpoint = %checkpoint.as_ref().unwrap().path().display(),
+                    "Resuming from checkpoint..."
+                );
+                season
+            }
+            None => {
+                let season = self.resolve_season(&sport_data_source, query).await?;
+                if let Some(checkpoint) = &mut checkpoint {
+                    checkpoint.season = Some(season.clone());
+                    checkpoint.save()?;
+                }
+                season
+            }
+        };
+        let SeasonCheckpoint {
+            competition,
+            season_id,
+            competitors,
+        } = season;
+
+        // Insert known competition
+        db.lock().unwrap().competitions.push(competition.clone());
+
+        // Replay the competitors done by a previous run
+        let mut report = RunReport::default();
+        if let Some(checkpoint) = &checkpoint {
+            for (competitor_id, stats_response) in &checkpoint.done {
+                report.succeeded.push(ScrapedCompetitor {
+                    id: competitor_id.clone(),
+                    name: stats_response.competitor.name.clone(),
+                });
+                consumer_callback(stats_response.clone(), competition.id.clone(), &db);
+            }
+            report.resumed = checkpoint.done.len();
+        }
+        let remaining = competitors
+            .into_iter()
+            .filter(|competitor| {
+                checkpoint
+                    .as_ref()
+                    .is_none_or(|c| !c.done.contains_key(&competitor.id))
+            })
+            .collect::<Vec<_>>();
+
+        self.warn_quota(&sport_data_source, remaining.len() as u64)?;
+
+        // Step 6: Fetch and process competitor statistics
+        let processed = async {
+            info!(
+                competitors = remaining.len(),
+                "Fetching and processing competitor statistics..."
+            );
+            self.process_competitor_stats(
+                &sport_data_source,
+                &season_id,
+                remaining.clone(),
+                &competition.id,
+                &db,
+                checkpoint.as_mut(),
+            )
+            .await
+        }
+        .instrument(info_span!("scrape_step", step = 6))
+        .await?;
+        report.merge(processed);
+
+        // Step 7: Refetch the failed competitors
+        if retry_failed && !report.is_complete() {
+            let failed = remaining
+                .into_iter()
+                .filter(|competitor| report.failed.iter().any(|f| f.id == competitor.id))
+                .collect::<Vec<_>>();
+            let retry = async {
+                info!(competitors = failed.len(), "Retrying failed competitors...");
+                self.process_competitor_stats(
+                    &sport_data_source,
+                    &season_id,
+                    failed,
+                    &competition.id,
+                    &db,
+                    checkpoint.as_mut(),
+                )
+                .await
+            }
+            .instrument(info_span!("scrape_step", step = 7))
+            .await?;
+            report.merge_retry(retry);
+        }
+
+        // A complete scrape has nothing left to resume
+        if let Some(checkpoint) = &checkpoint {
+            if report.is_complete() {
+                checkpoint.remove()?;
+            } else {
+                warn!(
+                    checkpoint = %checkpoint.path().display(),
+                    "Rerun the query to resume the failed competitors"
+                );
+            }
+        }
+
+        Ok(report)
+    }
+
+    async fn resolve_season(
+        &self,
+        sport_data_source: &Arc<SportRadarClient>,
+        query: &Query,
+    ) -> Result<SeasonCheckpoint> {
+        self.warn_quota(sport_data_source, 3)?;
+
         // Step 1: Get competitions
-        println!("Step 1: Fetching competitions...");
-        let competitions_response = self.get_competitions(&sport_data_source).await?;
+        let competitions_response = async {
+            info!("Fetching competitions...");
+            self.get_competitions(sport_data_source).await
+        }
+        .instrument(info_span!("scrape_step", step = 1))
+        .await?;
 
         // Step 2: Find the competition
-        println!("Step 2: Finding the competition...");
-        let competition = self.find_competition(&competitions_response, query)?;
+        let competition = info_span!("scrape_step", step = 2).in_scope(|| {
+            info!("Finding the competition...");
+            self.find_competition(&competitions_response, query)
+        })?;
 
         // Step 3: Get competition seasons
-        println!("Step 3: Fetching competition seasons...");
-        let seasons_response = self
-            .get_seasons(&sport_data_source, &competition.id)
-            .await?;
+        let seasons_response = async {
+            info!(competition_id = %competition.id, "Fetching competition seasons...");
+            self.get_seasons(sport_data_source, &competition.id).await
+        }
+        .instrument(info_span!("scrape_step", step = 3))
+        .await?;
 
         // Step 4: Find the season
-        println!("Step 4: Finding the season...");
-        let season = self.find_season(&seasons_response, query)?;
+        let season = info_span!("scrape_step", step = 4).in_scope(|| {
+            info!("Finding the season...");
+            self.find_season(&seasons_response, query)
+        })?;
+        let competition = EngineCompetition {
+            season_start: season.start_date,
+            season_end: season.end_date,
+            ..competition
+        };
 
         // Step 5: Get season competitors
-        println!("Step 5: Fetching season competitors...");
-        let competitors_response = self.get_competitors(&sport_data_source, &season.id).await?;
+        let competitors_response = async {
+            info!(season_id = %season.id, "Fetching season competitors...");
+            self.get_competitors(sport_data_source, &season.id).await
+        }
+        .instrument(info_span!("scrape_step", step = 5))
+        .await?;
 
-        // Step 6: Fetch and process competitor statistics
-        println!("Step 6: Fetching and processing competitor statistics...");
-        self.process_competitor_stats(
-            sport_data_source,
-            season.id,
-            competitors_response,
+        Ok(SeasonCheckpoint {
             competition,
-            db,
-        )
-        .await?;
+            season_id: season.id,
+            competitors: competitors_response.season_competitors,
+        })
+    }
 
+    /// Warns when the next steps need more requests than the monthly quota has left.
+    fn warn_quota(&self, client: &Arc<SportRadarClient>, needed: u64) -> Result<()> {
+        if let Some(remaining) = client.remaining_quota()? {
+            if needed > remaining {
+                warn!(
+                    needed,
+                    remaining,
+                    "The next step needs more requests than remain in this month's quota"
+                );
+            }
+        }
         Ok(())
     }
 
@@ -107,7 +423,7 @@ impl Scrapper {
             .ok_or_else(|| anyhow!("Competition not found"))?;
 
         Ok(EngineCompetition {
-            id: Arc::new(comp.id.clone()),
+            id: comp.id.clone(),
             name: comp.name.clone(),
             location: comp.category.name.clone(),
             gender: match comp.gender {
@@ -123,7 +439,7 @@ impl Scrapper {
     async fn get_seasons(
         &self,
         client: &Arc<SportRadarClient>,
-        competition_id: &str,
+        competition_id: &CompetitionId,
     ) -> Result<SeasonsResponse> {
         client
             .get_competition_seasons(competition_id)
@@ -132,15 +448,10 @@ impl Scrapper {
     }
 
     fn find_season(&self, seasons_response: &SeasonsResponse, query: &Query) -> Result<Season> {
-        seasons_response
-            .seasons
-            .iter()
-            .find(|season| {
-                NaiveDate::parse_from_str(&season.start_date, "%Y-%m-%d").unwrap()
-                    == query.season_start
-                    && NaiveDate::parse_from_str(&season.end_date, "%Y-%m-%d").unwrap()
-                        == query.season_end
-            })
+        query
+            .select_season(&seasons_response.seasons, |season| {
+                (season.start_date, season.end_date)
+            })?
             .cloned()
             .ok_or_else(|| anyhow!("Season not found"))
     }
@@ -148,7 +459,7 @@ impl Scrapper {
     async fn get_competitors(
         &self,
         client: &Arc<SportRadarClient>,
-        season_id: &str,
+        season_id: &SeasonId,
     ) -> Result<CompetitorsResponse> {
         client
             .get_season_competitors(season_id)
@@ -156,84 +467,88 @@ impl Scrapper {
             .map_err(|e| anyhow!("Failed to fetch season competitors: {}", e))
     }
 
-    async fn process_competitor_stats<'a>(
+    async fn process_competitor_stats(
         &self,
-        client: Arc<SportRadarClient>,
-        season_id: String,
-        competitors_response: CompetitorsResponse,
-        competition: EngineCompetition,
-        db: Arc<Mutex<Db>>,
-    ) -> Result<()> {
-        // Insert known competition
-        {
-            let mut db_lock = db.lock().unwrap();
-            db_lock.competitions.push(competition.clone());
-            drop(db_lock);
+        client: &Arc<SportRadarClient>,
+        season_id: &SeasonId,
+        competitors: Vec<Competitor>,
+        competition_id: &CompetitionId,
+        db: &Arc<Mutex<Db>>,
+        mut checkpoint: Option<&mut Checkpoint>,
+    ) -> Result<RunReport> {
+        // One task per competitor, the client bounds and adapts the requests in flight
+        let mut tasks = JoinSet::new();
+        for competitor in competitors {
+            let competition_id = competition_id.clone();
+            let client = Arc::clone(client);
+            let db = Arc::clone(db);
+            let season_id = season_id.clone();
+            let span = info_span!("competitor", competitor_id = %competitor.id);
+
+            tasks.spawn(
+                async move {
+                    let result =
+                        process_competitor(&season_id, &competitor, competition_id, client, db)
+                            .await;
+                    (competitor, result)
+                }
+                .instrument(span),
+            );
         }
 
-        // Divide and Conquer
-        let num_producers = 2;
-        let competitors_chunks = competitors_response
-            .season_competitors
-            .chunks(competitors_response.season_competitors.len() / num_producers)
-            .map(|chunk| chunk.to_vec())
-            .collect::<Vec<_>>();
-
-        let mut handles = vec![];
-        for chunks in competitors_chunks {
-            let competition_id_cloned = Arc::clone(&competition.id);
-            let client_cloned = Arc::clone(&client);
-            let db_cloned = Arc::clone(&db);
-            let season_id_cloned = season_id.clone();
-
-            let future = async move {
-                for competitor in chunks {
-                    let db_clone_for_competitor = Arc::clone(&db_cloned);
-                    if let Err(e) = process_competitor(
-                        &season_id_cloned,
-                        &competitor,
-                        Arc::clone(&competition_id_cloned),
-                        Arc::clone(&client_cloned),
-                        db_clone_for_competitor,
-                    )
-                    .await
-                    {
-                        eprint!(
-                            "process_competitor failed for competitor {}:{}",
-                            competitor.id, e
-                        );
+        let mut report = RunReport::default();
+        while let Some(task) = tasks.join_next().await {
+            let (competitor, result) = task?;
+            match result {
+                Ok(stats_response) => {
+                    if let Some(checkpoint) = checkpoint.as_deref_mut() {
+                        checkpoint
+                            .done
+                            .insert(competitor.id.clone(), stats_response);
+                        checkpoint.save()?;
                     }
+                    report.succeeded.push(ScrapedCompetitor {
+                        id: competitor.id,
+                        name: competitor.name,
+                    });
                 }
-            };
-
-            let handle = tokio::task::spawn(future);
-            handles.push(handle);
-        }
-
-        for handle in handles {
-            let _ = tokio::try_join!(handle)?;
+                Err(e) => {
+                    error!(competitor_id = %competitor.id, error = %e, "process_competitor failed");
+                    report.failed.push(FailedCompetitor {
+                        id: competitor.id,
+                        name: competitor.name,
+                        reason: e.to_string(),
+                    });
+                }
+            }
         }
-
-        Ok(())
+        info!(
+            succeeded = report.succeeded.len(),
+            failed = report.failed.len(),
+            concurrency = client.concurrency_limit(),
+            coalesced = client.coalesced_requests(),
+            "Competitor statistics processed"
+        );
+
+        Ok(report)
     }
 }
 
-async fn process_competitor<'a>(
-    season_id: &'a str,
+async fn process_competitor(
+    season_id: &SeasonId,
     competitor: &Competitor,
-    competition_id: Arc<String>,
+    competition_id: CompetitionId,
     client: Arc<SportRadarClient>,
     db: Arc<Mutex<Db>>,
-) -> Result<()> {
-    let message: Result<PlayerStatisticsResponse> =
-        producer_callback(season_id, &competitor.id, client.clone()).await;
-    consumer_callback(message, competition_id, db).await;
-    Ok(())
+) -> Result<PlayerStatisticsResponse> {
+    let message = producer_callback(season_id, &competitor.id, client).await?;
+    consumer_callback(message.clone(), competition_id, &db);
+    Ok(message)
 }
 
-async fn producer_callback<'a>(
-    season_id: &'a str,
-    competitor_id: &str,
+async fn producer_callback(
+    season_id: &SeasonId,
+    competitor_id: &CompetitorId,
     client: Arc<SportRadarClient>,
 ) -> Result<PlayerStatisticsResponse> {
     client
@@ -242,62 +557,194 @@ async fn producer_callback<'a>(
         .map_err(|e| anyhow!("Failed to fetch competitor statistics: {}", e))
 }
 
-async fn consumer_callback(
-    message: Result<PlayerStatisticsResponse>,
-    competition_id: Arc<String>,
-    db: Arc<Mutex<Db>>,
+fn consumer_callback(
+    stats_response: PlayerStatisticsResponse,
+    competition_id: CompetitionId,
+    db: &Arc<Mutex<Db>>,
 ) {
-    match message {
-        Err(e) => println!("Consumer received error msg: {}", e),
-        Ok(stats_response) => {
-            let team_id = Arc::new(stats_response.competitor.id);
-            let team = Team {
-                id: Arc::clone(&team_id),
-                name: stats_response.competitor.name,
-                abbreviation: stats_response.competitor.abbreviation,
-            };
-
-            let mut players = vec![];
-            let mut players_stats = vec![];
-
-            for player_stat in stats_response.competitor.players {
-                let player_id = Arc::new(player_stat.id);
-                let player = Player {
-                    id: Arc::clone(&player_id),
-                    name: player_stat.name,
-                };
-
-                players.push(player);
-
-                let metrics = vec![
-                    RepoMetric::GoalsScored {
-                        value: player_stat.statistics.goals_scored,
-                    },
-                    RepoMetric::Assists {
-                        value: player_stat.statistics.assists,
-                    },
-                ];
-
-                let player_stats = PlayerStats {
-                    player_id: Arc::clone(&player_id),
-                    team_id: Arc::clone(&team_id),
-                    competition_id: Arc::clone(&competition_id),
-                    metrics,
-                };
-
-                players_stats.push(player_stats);
-            }
+    let team_id = stats_response.competitor.id;
+    let team = Team {
+        id: team_id.clone(),
+        name: stats_response.competitor.name,
+        abbreviation: stats_response.competitor.abbreviation,
+    };
+    let team_stats = TeamStats {
+        team_id: team_id.clone(),
+        competition_id: competition_id.clone(),
+        metrics: team_metrics(&stats_response.competitor.statistics),
+    };
+
+    let mut players = vec![];
+    let mut players_stats = vec![];
+
+    for player_stat in stats_response.competitor.players {
+        let player_id = player_stat.id;
+        let player = Player {
+            id: player_id.clone(),
+            name: player_stat.name,
+        };
+
+        players.push(player);
+
+        let metrics = player_metrics(&player_stat.statistics);
+
+        let player_stats = PlayerStats {
+            player_id,
+            team_id: team_id.clone(),
+            competition_id: competition_id.clone(),
+            metrics,
+        };
+
+        players_stats.push(player_stats);
+    }
 
-            // Aquire db lock
-            let mut db_lock = db.lock().unwrap();
-            db_lock.teams.push(team);
-            for player in players {
-                db_lock.players.push(player);
-            }
-            for player_stats in players_stats {
-                db_lock.players_stats.push(player_stats);
-            }
-            drop(db_lock);
-        }
+    // Aquire db lock
+    let mut db_lock = db.lock().unwrap();
+    db_lock.teams.push(team);
+    db_lock.teams_stats.push(team_stats);
+    for player in players {
+        db_lock.players.push(player);
+    }
+    for player_stats in players_stats {
+        db_lock.players_stats.push(player_stats);
+    }
+    drop(db_lock);
+}
+
+/// The statistics reported for the player, those the provider left out are not made up as zero.
+fn player_metrics(statistics: &PlayerStatistics) -> Vec<RepoMetric> {
+    [
+        statistics
+            .goals_scored
+            .map(|value| RepoMetric::GoalsScored { value }),
+        statistics
+            .assists
+            .map(|value| RepoMetric::Assists { value }),
+        statistics
+            .goals_by_head
+            .map(|value| RepoMetric::GoalsByHead { value }),
+        statistics
+            .goals_by_penalty
+            .map(|value| RepoMetric::GoalsByPenalty { value }),
+        statistics
+            .goals_conceded
+            .map(|value| RepoMetric::GoalsConceded { value }),
+        statistics
+            .matches_played
+            .map(|value| RepoMetric::MatchesPlayed { value }),
+        statistics
+            .own_goals
+            .map(|value| RepoMetric::OwnGoals { value }),
+        statistics
+            .penalties_missed
+            .map(|value| RepoMetric::PenaltiesMissed { value }),
+        statistics
+            .red_cards
+            .map(|value| RepoMetric::RedCards { value }),
+        statistics
+            .substituted_in
+            .map(|value| RepoMetric::SubstitutedIn { value }),
+        statistics
+            .substituted_out
+            .map(|value| RepoMetric::SubstitutedOut { value }),
+        statistics
+            .yellow_cards
+            .map(|value| RepoMetric::YellowCards { value }),
+        statistics
+            .yellow_red_cards
+            .map(|value| RepoMetric::YellowRedCards { value }),
+        statistics
+            .cards_given
+            .map(|value| RepoMetric::CardsGiven { value }),
+        statistics
+            .offsides
+            .map(|value| RepoMetric::Offsides { value }),
+        statistics
+            .shots_off_target
+            .map(|value| RepoMetric::ShotsOffTarget { value }),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+fn team_metrics(statistics: &CompetitorStatistics) -> Vec<TeamMetric> {
+    let mut metrics = vec![
+        TeamMetric::AverageBallPossession {
+            value: statistics.average_ball_possession,
+        },
+        TeamMetric::CardsGiven {
+            value: statistics.cards_given.into(),
+        },
+        TeamMetric::CornerKicks {
+            value: statistics.corner_kicks.into(),
+        },
+        TeamMetric::FreeKicks {
+            value: statistics.free_kicks.into(),
+        },
+        TeamMetric::GoalsByFoot {
+            value: statistics.goals_by_foot.into(),
+        },
+        TeamMetric::GoalsByHead {
+            value: statistics.goals_by_head.into(),
+        },
+        TeamMetric::GoalsConceded {
+            value: statistics.goals_conceded.into(),
+        },
+        TeamMetric::GoalsConcededFirstHalf {
+            value: statistics.goals_conceded_first_half.into(),
+        },
+        TeamMetric::GoalsConcededSecondHalf {
+            value: statistics.goals_conceded_second_half.into(),
+        },
+        TeamMetric::GoalsScored {
+            value: statistics.goals_scored.into(),
+        },
+        TeamMetric::GoalsScoredFirstHalf {
+            value: statistics.goals_scored_first_half.into(),
+        },
+        TeamMetric::GoalsScoredSecondHalf {
+            value: statistics.goals_scored_second_half.into(),
+        },
+        TeamMetric::MatchesPlayed {
+            value: statistics.matches_played.into(),
+        },
+        TeamMetric::Offsides {
+            value: statistics.offsides.into(),
+        },
+        TeamMetric::RedCards {
+            value: statistics.red_cards.into(),
+        },
+        TeamMetric::ShotsBlocked {
+            value: statistics.shots_blocked.into(),
+        },
+        TeamMetric::ShotsOffTarget {
+            value: statistics.shots_off_target.into(),
+        },
+        TeamMetric::ShotsOnBar {
+            value: statistics.shots_on_bar.into(),
+        },
+        TeamMetric::ShotsOnPost {
+            value: statistics.shots_on_post.into(),
+        },
+        TeamMetric::ShotsOnTarget {
+            value: statistics.shots_on_target.into(),
+        },
+        TeamMetric::ShotsTotal {
+            value: statistics.shots_total.into(),
+        },
+        TeamMetric::YellowCards {
+            value: statistics.yellow_cards.into(),
+        },
+        TeamMetric::YellowRedCards {
+            value: statistics.yellow_red_cards.into(),
+        },
+    ];
+    // Only reported by the provider for some competitions
+    if let Some(penalties_missed) = statistics.penalties_missed {
+        metrics.push(TeamMetric::PenaltiesMissed {
+            value: penalties_missed.into(),
+        });
     }
+    metrics
 }
@@ -0,0 +1,156 @@
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::db::Db;
+use crate::scrapper::Query;
+
+/// The first line of every snapshot file, followed by its format version.
+const MAGIC: &str = "SPORT-CLI-SNAPSHOT";
+/// The snapshot format written by this version. Bump it on any change to the header or `Db`.
+pub const FORMAT_VERSION: u32 = 2;
+/// The extension of snapshot files.
+pub const EXTENSION: &str = "snapshot";
+
+/// Describes a snapshot, readable without decompressing its data.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotHeader {
+    pub format_version: u32,
+    /// The data provider, such as "sportradar".
+    pub provider: String,
+    /// When the data was fetched from the provider.
+    pub fetched_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    /// The competition season the data covers.
+    pub scope: Query,
+}
+
+/// A frozen `Db`, to share a dataset and reproduce an analysis exactly.
+///
+/// The file holds a `SPORT-CLI-SNAPSHOT <format version>` line, the json header on one line,
+/// then the gzip compressed json `Db`.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub header: SnapshotHeader,
+    pub db: Db,
+}
+
+impl Snapshot {
+    pub fn new(provider: &str, fetched_at: DateTime<Utc>, scope: Query, db: Db) -> Self {
+        Snapshot {
+            header: SnapshotHeader {
+                format_version: FORMAT_VERSION,
+                provider: provider.to_string(),
+                fetched_at,
+                created_at: Utc::now(),
+                scope,
+            },
+            db,
+        }
+    }
+
+    /// Writes the snapshot, through a temporary file so a crash never leaves it half written,
+    /// nor an overwritten snapshot lost.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp = path.with_extension(format!("{}.tmp", EXTENSION));
+        let write = || -> Result<()> {
+            let mut file = BufWriter::new(File::create(&tmp)?);
+            writeln!(file, "{} {}", MAGIC, self.header.format_version)?;
+            serde_json::to_writer(&mut file, &self.header)?;
+            writeln!(file)?;
+            let mut encoder = GzEncoder::new(file, Compression::default());
+            serde_json::to_writer(&mut encoder, &self.db)?;
+            encoder.finish()?.into_inner()?.sync_all()?;
+            fs::rename(&tmp, path)?;
+            Ok(())
+        };
+        write().map_err(|e| {
+            let _ = fs::remove_file(&tmp);
+            anyhow!("Failed to write snapshot {}: {}", path.display(), e)
+        })
+    }
+
+    pub fn read(path: &Path) -> Result<Self> {
+        let mut reader = open(path)?;
+        let header = read_header(&mut reader, path)?;
+        let mut json = String::new();
+        GzDecoder::new(reader)
+            .read_to_string(&mut json)
+            .map_err(|e| anyhow!("Failed to decompress snapshot {}: {}", path.display(), e))?;
+        let db = serde_json::from_str(&json)
+            .map_err(|e| anyhow!("Invalid snapshot data {}: {}", path.display(), e))?;
+        Ok(Snapshot { header, db })
+    }
+
+    /// Reads only the header, as to list snapshots.
+    pub fn read_header(path: &Path) -> Result<SnapshotHeader> {
+        read_header(&mut open(path)?, path)
+    }
+}
+
+fn open(path: &Path) -> Result<BufReader<File>> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|e| anyhow!("Failed to open snapshot {}: {}", path.display(), e))
+}
+
+fn read_header(reader: &mut impl BufRead, path: &Path) -> Result<SnapshotHeader> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let version = line
+        .trim_end()
+        .strip_prefix(MAGIC)
+        .and_then(|version| version.trim().parse::<u32>().ok())
+        .ok_or_else(|| anyhow!("Not a sport-cli snapshot: {}", path.display()))?;
+    if version > FORMAT_VERSION {
+        return Err(anyhow!(
+            "Snapshot {} has format version {}, newer than the supported {}, upgrade sport-cli",
+            path.display(),
+            version,
+            FORMAT_VERSION
+        ));
+    }
+
+    line.clear();
+    reader.read_line(&mut line)?;
+    serde_json::from_str(&line)
+        .map_err(|e| anyhow!("Invalid snapshot header {}: {}", path.display(), e))
+}
+
+/// Resolves a snapshot name, such as `pl-2023`, to its file in the snapshots directory.
+/// Paths to files are kept as they are.
+pub fn resolve(dir: &Path, name: &str) -> PathBuf {
+    let path = Path::new(name);
+    if path.components().count() > 1 || path.extension().is_some() {
+        path.to_path_buf()
+    } else {
+        dir.join(format!("{}.{}", name, EXTENSION))
+    }
+}
+
+/// The snapshots in the directory, with their headers (or why they cannot be read), by name.
+pub fn list(dir: &Path) -> Result<Vec<(PathBuf, Result<SnapshotHeader>)>> {
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    let mut paths = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    paths.retain(|path| path.extension().is_some_and(|ext| ext == EXTENSION));
+    paths.sort();
+    Ok(paths
+        .into_iter()
+        .map(|path| {
+            let header = Snapshot::read_header(&path);
+            (path, header)
+        })
+        .collect())
+}
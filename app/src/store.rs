@@ -0,0 +1,110 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use engine::repo::{
+    in_memo::InMemoRepository, model::Competition, sqlite::SqliteStorage, storage::Storage,
+};
+
+use crate::db::Db;
+use crate::scrapper::Query;
+
+/// The data synced with `sport-cli sync`, kept in a SQLite database and queried offline.
+#[derive(Debug, Clone)]
+pub struct Store {
+    path: PathBuf,
+}
+
+impl Store {
+    pub fn new(path: PathBuf) -> Self {
+        Store { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The synced data of the query competition season, if any, with when it was fetched.
+    pub fn load(&self, query: &Query) -> Result<Option<(Db, Option<DateTime<Utc>>)>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        let storage = SqliteStorage::open(&self.path)?;
+        let competitions = storage
+            .competitions()?
+            .into_iter()
+            .filter(|competition| query.is_competition(competition));
+        let Some(season) = query.select_season(competitions, |competition| {
+            (competition.season_start, competition.season_end)
+        })?
+        else {
+            return Ok(None);
+        };
+
+        let mut db = Db::new();
+        storage.load_season(
+            &season,
+            &mut db.competitions,
+            &mut db.teams,
+            &mut db.players,
+            &mut db.players_stats,
+            &mut db.teams_stats,
+        )?;
+        Ok(Some((db, storage.synced_at(&season)?)))
+    }
+
+    /// The synced seasons of the query competition, whatever their dates,
+    /// with their number of competitors.
+    pub fn seasons(&self, query: &Query) -> Result<Vec<(Competition, usize)>> {
+        if !self.path.exists() {
+            return Ok(vec![]);
+        }
+        let storage = SqliteStorage::open(&self.path)?;
+        storage
+            .competitions()?
+            .into_iter()
+            .filter(|competition| query.is_competition(competition))
+            .map(|season| {
+                let competitors = storage.team_stats(&season)?.len();
+                Ok((season, competitors))
+            })
+            .collect()
+    }
+
+    /// Adds the data fetched at the given time,
+    /// replacing what was synced before for the same competition seasons,
+    /// down to the stats of players no longer in them.
+    pub fn save(&self, db: &Db, synced_at: DateTime<Utc>) -> Result<()> {
+        let storage = SqliteStorage::open(&self.path)?;
+        storage.transaction(|storage| {
+            for competition in db.competitions.all().values() {
+                storage.save_competition(competition)?;
+                storage.clear_season_stats(competition)?;
+                storage.mark_synced(competition, synced_at)?;
+            }
+            for team in db.teams.all().values() {
+                storage.save_team(team)?;
+            }
+            for player in db.players.all().values() {
+                storage.save_player(player)?;
+            }
+            for player_stats in db.players_stats.all().values() {
+                let season = db
+                    .competitions
+                    .find(&player_stats.competition_id)
+                    .ok_or_else(|| {
+                        anyhow!("Unknown competition {}", player_stats.competition_id)
+                    })?;
+                storage.save_player_stats(season, player_stats)?;
+            }
+            for team_stats in db.teams_stats.all().values() {
+                let season = db
+                    .competitions
+                    .find(&team_stats.competition_id)
+                    .ok_or_else(|| anyhow!("Unknown competition {}", team_stats.competition_id))?;
+                storage.save_team_stats(season, team_stats)?;
+            }
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,138 @@
+use anyhow::Result;
+use app::checkpoint::Checkpoint;
+use app::cli::analytics::run::{run, RunOptions};
+use app::db::Db;
+use app::scrapper::Scrapper;
+use app::store::Store;
+use engine::engine::Query as EngineQuery;
+use engine::repo::in_memo::InMemoRepository;
+use std::sync::{Arc, Mutex};
+
+mod fixture;
+use fixture::{query, statistics_of, temp_dir, Provider};
+
+/// Scrapes 5 competitors, with the api key running out of quota after 3 of them.
+async fn interrupted_scrape(provider: &Provider, scrapper: &Scrapper) -> Result<()> {
+    provider.limit_quota(3);
+    let report = scrapper
+        .execute(
+            Arc::new(provider.client()),
+            &query(),
+            Arc::new(Mutex::new(Db::new())),
+            false,
+        )
+        .await?;
+    assert_eq!(report.succeeded.len(), 3);
+    assert_eq!(report.failed.len(), 2);
+    provider.restore_quota();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_resume_an_interrupted_scrape() -> Result<()> {
+    let provider = Provider::start(5).await;
+    let checkpoints = temp_dir("resume");
+    let scrapper = Scrapper::default().with_checkpoints(checkpoints.clone());
+    interrupted_scrape(&provider, &scrapper).await?;
+
+    let checkpoint = Checkpoint::load(&checkpoints, &query())?;
+    assert!(checkpoint.season.is_some());
+    let done: Vec<String> = checkpoint.done.keys().map(|id| id.to_string()).collect();
+    assert_eq!(done.len(), 3);
+
+    let sent = provider.requests().len();
+    let db = Arc::new(Mutex::new(Db::new()));
+    let report = scrapper
+        .execute(Arc::new(provider.client()), &query(), db.clone(), false)
+        .await?;
+
+    assert!(report.is_complete(), "{}", report);
+    assert_eq!(report.resumed, 3);
+    assert_eq!(report.succeeded.len(), 5);
+    assert_eq!(db.lock().unwrap().teams.all().len(), 5);
+
+    // Only the statistics of the competitors left are fetched, without any lookup
+    let mut fetched = provider.requests()[sent..]
+        .iter()
+        .map(|path| statistics_of(path).expect("Statistics request"))
+        .collect::<Vec<_>>();
+    fetched.sort();
+    let mut left: Vec<String> = provider.competitors()[..5]
+        .iter()
+        .filter(|id| !done.contains(id))
+        .cloned()
+        .collect();
+    left.sort();
+    assert_eq!(fetched, left);
+
+    // A complete scrape leaves no checkpoint behind
+    assert!(!checkpoint.path().exists());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_restart_discards_the_checkpoint() -> Result<()> {
+    let provider = Provider::start(5).await;
+    let checkpoints = temp_dir("restart");
+    let scrapper = Scrapper::default().with_checkpoints(checkpoints.clone());
+    interrupted_scrape(&provider, &scrapper).await?;
+
+    let sent = provider.requests().len();
+    scrapper.restart(&query())?;
+    let report = scrapper
+        .execute(
+            Arc::new(provider.client()),
+            &query(),
+            Arc::new(Mutex::new(Db::new())),
+            false,
+        )
+        .await?;
+
+    assert_eq!(report.resumed, 0);
+    assert_eq!(report.succeeded.len(), 5);
+    // 3 lookups and the statistics of every competitor
+    assert_eq!(provider.requests().len() - sent, 8);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dry_run_keeps_the_checkpoint_despite_restart() -> Result<()> {
+    let provider = Provider::start(5).await;
+    let checkpoints = temp_dir("dry-run");
+    let scrapper = Scrapper::default().with_checkpoints(checkpoints.clone());
+    interrupted_scrape(&provider, &scrapper).await?;
+
+    let query: EngineQuery =
+        "top 5 players by goals in \"Premier League\" England 2023-08-11..2024-05-19"
+            .parse()
+            .map_err(anyhow::Error::msg)?;
+    let sent = provider.requests().len();
+    let options = RunOptions {
+        dry_run: true,
+        restart: true,
+        ..Default::default()
+    };
+    let store = Store::new(checkpoints.join("store.sqlite"));
+    run(
+        provider.client(),
+        checkpoints.clone(),
+        &store,
+        query,
+        None,
+        options,
+    )
+    .await?;
+
+    assert_eq!(
+        provider.requests().len(),
+        sent,
+        "A dry run calls no endpoint"
+    );
+    assert_eq!(
+        Checkpoint::load(&checkpoints, &fixture::query())?
+            .done
+            .len(),
+        3
+    );
+    Ok(())
+}
@@ -0,0 +1,215 @@
+// Shared by the test files, which each use part of it
+#![allow(dead_code)]
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use app::db::Db;
+use app::scrapper::Query;
+use chrono::NaiveDate;
+use engine::repo::in_memo::InMemoRepository;
+use engine::repo::model::{
+    Competition, Gender, Metric, Player, PlayerStats, Team, TeamMetric, TeamStats,
+};
+use sport_radar::client::SportRadarClient;
+use sport_radar::config::ConfigLayer;
+use sport_radar::testing::MockServer;
+
+const COMPETITIONS: &str = include_str!("../../sport-radar/resources/api/competitions.json");
+const SEASONS: &str = include_str!("../../sport-radar/resources/api/competition_seasons.json");
+const COMPETITORS: &str = include_str!("../../sport-radar/resources/api/season_competitors.json");
+const STATISTICS: &str =
+    include_str!("../../sport-radar/resources/api/seasonal_competitor_statistics.json");
+
+/// The Premier League 23/24 season, served by the provider fixture.
+pub fn query() -> Query {
+    Query {
+        event: "Premier League".to_string(),
+        location: "England".to_string(),
+        gender: Gender::Male,
+        season_start: NaiveDate::from_ymd_opt(2023, 8, 11).unwrap(),
+        season_end: NaiveDate::from_ymd_opt(2024, 5, 19).unwrap(),
+    }
+}
+
+/// The Premier League 23/24 season with a single team, whose players scored 1, 2, 3... goals.
+pub fn season_db(players: &[&str]) -> Db {
+    let query = query();
+    let competition = Competition {
+        id: "sr:competition:17".parse().unwrap(),
+        name: query.event,
+        location: query.location,
+        gender: query.gender,
+        season_start: query.season_start,
+        season_end: query.season_end,
+    };
+    let team = Team {
+        id: "sr:competitor:44".parse().unwrap(),
+        name: "Liverpool FC".to_string(),
+        abbreviation: "LIV".to_string(),
+    };
+
+    let mut db = Db::new();
+    db.teams_stats.push(TeamStats {
+        team_id: team.id.clone(),
+        competition_id: competition.id.clone(),
+        metrics: vec![TeamMetric::GoalsScored { value: 86.0 }],
+    });
+    for (index, name) in players.iter().enumerate() {
+        let player = Player {
+            id: format!("sr:player:{}", index + 1).parse().unwrap(),
+            name: name.to_string(),
+        };
+        db.players_stats.push(PlayerStats {
+            player_id: player.id.clone(),
+            team_id: team.id.clone(),
+            competition_id: competition.id.clone(),
+            metrics: vec![Metric::GoalsScored {
+                value: index as u32 + 1,
+            }],
+        });
+        db.players.push(player);
+    }
+    db.teams.push(team);
+    db.competitions.push(competition);
+    db
+}
+
+/// A new empty directory, unique to the calling test.
+pub fn temp_dir(name: &str) -> PathBuf {
+    static COUNT: AtomicU32 = AtomicU32::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .subsec_nanos();
+    let dir = std::env::temp_dir().join(format!(
+        "sport-cli-{}-{}-{}-{}",
+        name,
+        std::process::id(),
+        nanos,
+        COUNT.fetch_add(1, Ordering::SeqCst)
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[derive(Debug, Default)]
+struct Faults {
+    /// How many more competitor statistics the api key may fetch, unlimited if none.
+    quota: Option<u32>,
+    /// Competitors whose next statistics request fails.
+    failing: HashSet<String>,
+}
+
+/// The provider api, serving the first competitors of the season fixture.
+pub struct Provider {
+    server: MockServer,
+    faults: Arc<Mutex<Faults>>,
+}
+
+impl Provider {
+    pub async fn start(competitors: usize) -> Provider {
+        let mut season_competitors: serde_json::Value = serde_json::from_str(COMPETITORS).unwrap();
+        season_competitors["season_competitors"]
+            .as_array_mut()
+            .unwrap()
+            .truncate(competitors);
+        let season_competitors = season_competitors.to_string();
+
+        let faults = Arc::new(Mutex::new(Faults::default()));
+        let handler_faults = faults.clone();
+        let server = MockServer::start(move |path| {
+            if path.ends_with("/competitions.json") {
+                return (200, COMPETITIONS.to_string());
+            }
+            if path.ends_with("/seasons.json") {
+                return (200, SEASONS.to_string());
+            }
+            if path.ends_with("/competitors.json") {
+                return (200, season_competitors.clone());
+            }
+            let Some(competitor_id) = statistics_of(path) else {
+                return (404, "Not found".to_string());
+            };
+            let mut faults = handler_faults.lock().unwrap();
+            if faults.failing.remove(&competitor_id) {
+                return (500, "Internal error".to_string());
+            }
+            match &mut faults.quota {
+                Some(0) => return (403, "Quota exceeded".to_string()),
+                Some(quota) => *quota -= 1,
+                None => {}
+            }
+            (200, STATISTICS.replace("sr:competitor:44", &competitor_id))
+        })
+        .await
+        .unwrap();
+
+        Provider { server, faults }
+    }
+
+    /// The competitor ids of the season, in the provider order.
+    pub fn competitors(&self) -> Vec<String> {
+        let season_competitors: serde_json::Value = serde_json::from_str(COMPETITORS).unwrap();
+        season_competitors["season_competitors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|competitor| competitor["id"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    /// Runs the api key out of quota after the given number of competitor statistics.
+    pub fn limit_quota(&self, statistics: u32) {
+        self.faults.lock().unwrap().quota = Some(statistics);
+    }
+
+    /// Gives the api key its quota back.
+    pub fn restore_quota(&self) {
+        self.faults.lock().unwrap().quota = None;
+    }
+
+    /// Fails the next statistics request of the competitor.
+    pub fn fail_once(&self, competitor_id: &str) {
+        self.faults
+            .lock()
+            .unwrap()
+            .failing
+            .insert(competitor_id.to_string());
+    }
+
+    /// A client of this provider with a single api key, as fast as the provider answers.
+    pub fn client(&self) -> SportRadarClient {
+        let config = ConfigLayer {
+            api_key: Some("test-key".to_string()),
+            rate_limit: Some(0.0),
+            ..Default::default()
+        }
+        .resolve()
+        .unwrap();
+        SportRadarClient::new(config).with_base_url(self.server.base_url())
+    }
+
+    /// The paths requested so far, in order.
+    pub fn requests(&self) -> Vec<String> {
+        self.server.requests()
+    }
+
+    /// The competitors whose statistics were requested so far, in order.
+    pub fn statistics_requests(&self) -> Vec<String> {
+        self.requests()
+            .iter()
+            .filter_map(|path| statistics_of(path))
+            .collect()
+    }
+}
+
+/// The competitor id of a statistics request path.
+pub fn statistics_of(path: &str) -> Option<String> {
+    let path = path.strip_suffix("/statistics.json")?;
+    let (_, competitor_id) = path.rsplit_once("/competitors/")?;
+    Some(competitor_id.to_string())
+}
@@ -0,0 +1,144 @@
+use anyhow::Result;
+use app::checkpoint::{Checkpoint, SeasonCheckpoint};
+use app::db::Db;
+use app::plan::ASSUMED_COMPETITORS;
+use app::scrapper::{Query, Scrapper};
+use app::store::Store;
+use chrono::{NaiveDate, Utc};
+use engine::repo::competitions::CompetitionsRepo;
+use engine::repo::in_memo::InMemoRepository;
+use engine::repo::model::Competition;
+use sport_radar::client::SportRadarClient;
+use sport_radar::config::ConfigLayer;
+use sport_radar::model::Competitor;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+mod fixture;
+use fixture::{query, season_db, temp_dir, Provider};
+
+/// The season before the queried one.
+fn previous_season() -> Query {
+    Query {
+        season_start: NaiveDate::from_ymd_opt(2022, 8, 5).unwrap(),
+        season_end: NaiveDate::from_ymd_opt(2023, 5, 28).unwrap(),
+        ..query()
+    }
+}
+
+fn competition(season: &Query) -> Competition {
+    Competition {
+        id: "sr:competition:17".parse().unwrap(),
+        name: season.event.clone(),
+        location: season.location.clone(),
+        gender: season.gender.clone(),
+        season_start: season.season_start,
+        season_end: season.season_end,
+    }
+}
+
+fn client(rate_limit: f64) -> Result<SportRadarClient> {
+    let config = ConfigLayer {
+        api_key: Some("test-key".to_string()),
+        rate_limit: Some(rate_limit),
+        ..Default::default()
+    }
+    .resolve()?;
+    Ok(SportRadarClient::new(config))
+}
+
+#[test]
+fn test_assume_competitors_without_local_data() -> Result<()> {
+    let dir = temp_dir("plan");
+    let scrapper = Scrapper::default()
+        .with_checkpoints(dir.join("checkpoints"))
+        .with_store(Store::new(dir.join("store.sqlite")));
+
+    let plan = scrapper.plan(&client(0.0)?, &query())?;
+
+    assert_eq!(plan.competitors.competitors, ASSUMED_COMPETITORS);
+    assert_eq!(plan.network_calls(), 3 + ASSUMED_COMPETITORS);
+    assert_eq!(plan.estimated_duration(), None);
+    let text = plan.to_string();
+    assert!(text.contains("(assuming 20 competitors)"), "{}", text);
+    assert!(text.contains("not rate limited"), "{}", text);
+    Ok(())
+}
+
+#[test]
+fn test_estimate_competitors_from_another_season_checkpoint() -> Result<()> {
+    let checkpoints = temp_dir("plan");
+    let mut checkpoint = Checkpoint::load(&checkpoints, &previous_season())?;
+    let competitors = (1..=18)
+        .map(|id| Competitor {
+            id: format!("sr:competitor:{}", id).parse().unwrap(),
+            name: format!("Team {}", id),
+            short_name: format!("Team {}", id),
+            abbreviation: format!("T{}", id),
+        })
+        .collect();
+    checkpoint.season = Some(SeasonCheckpoint {
+        competition: competition(&previous_season()),
+        season_id: "sr:season:93741".parse().unwrap(),
+        competitors,
+    });
+    checkpoint.save()?;
+    let scrapper = Scrapper::default().with_checkpoints(checkpoints);
+
+    let plan = scrapper.plan(&client(2.0)?, &query())?;
+
+    assert_eq!(plan.competitors.competitors, 18);
+    assert_eq!(plan.competitors.from, Some(previous_season()));
+    assert_eq!(plan.network_calls(), 3 + 18);
+    assert_eq!(
+        plan.estimated_duration(),
+        Some(Duration::from_millis(10_500))
+    );
+    let text = plan.to_string();
+    assert!(
+        text.contains("(assuming 18 competitors, as from 2022-08-05 to 2023-05-28)"),
+        "{}",
+        text
+    );
+    Ok(())
+}
+
+#[test]
+fn test_estimate_competitors_from_a_synced_season() -> Result<()> {
+    let dir = temp_dir("plan");
+    let store = Store::new(dir.join("store.sqlite"));
+    let mut db: Db = season_db(&["Salah, Mohamed"]);
+    let mut competitions = CompetitionsRepo::new();
+    competitions.push(competition(&previous_season()));
+    db.competitions = competitions;
+    store.save(&db, Utc::now())?;
+    let scrapper = Scrapper::default().with_store(store);
+
+    let plan = scrapper.plan(&client(0.0)?, &query())?;
+
+    assert_eq!(plan.competitors.competitors, 1);
+    assert_eq!(plan.competitors.from, Some(previous_season()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_plan_only_the_calls_left_by_a_checkpoint() -> Result<()> {
+    let provider = Provider::start(5).await;
+    let checkpoints = temp_dir("plan");
+    let scrapper = Scrapper::default().with_checkpoints(checkpoints);
+    provider.limit_quota(3);
+    scrapper
+        .execute(
+            Arc::new(provider.client()),
+            &query(),
+            Arc::new(Mutex::new(Db::new())),
+            false,
+        )
+        .await?;
+
+    let plan = scrapper.plan(&client(0.0)?, &query())?;
+
+    assert_eq!(plan.network_calls(), 2, "{}", plan);
+    assert!(plan.calls.iter().take(3).all(|call| call.cached == 1));
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use anyhow::Result;
+use app::cli::analytics::run::{run, RunOptions};
+use app::db::Db;
+use app::report::RunReport;
+use app::scrapper::Scrapper;
+use app::store::Store;
+use engine::engine::Query as EngineQuery;
+use std::sync::{Arc, Mutex};
+
+mod fixture;
+use fixture::{query, temp_dir, Provider};
+
+fn engine_query() -> EngineQuery {
+    "top 5 players by goals in \"Premier League\" England 2023-08-11..2024-05-19"
+        .parse()
+        .unwrap()
+}
+
+/// Scrapes 3 competitors, the statistics of the second one failing once.
+async fn scrape(provider: &Provider, retry_failed: bool) -> Result<RunReport> {
+    provider.fail_once(&provider.competitors()[1]);
+    Scrapper::default()
+        .execute(
+            Arc::new(provider.client().with_max_retries(0)),
+            &query(),
+            Arc::new(Mutex::new(Db::new())),
+            retry_failed,
+        )
+        .await
+}
+
+#[tokio::test]
+async fn test_report_failed_competitors() -> Result<()> {
+    let provider = Provider::start(3).await;
+
+    let report = scrape(&provider, false).await?;
+
+    assert!(!report.is_complete());
+    assert_eq!(report.succeeded.len(), 2);
+    assert_eq!(report.failed.len(), 1);
+    let failed = &report.failed[0];
+    assert_eq!(failed.id.to_string(), provider.competitors()[1]);
+    assert!(failed.reason.contains("500"), "{}", failed.reason);
+    let banner = report.to_string();
+    assert!(banner.starts_with("Scraped 2/3 competitors"), "{}", banner);
+    assert!(banner.contains("INCOMPLETE"), "{}", banner);
+    assert!(banner.contains(&failed.name), "{}", banner);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_retry_only_the_failed_competitors() -> Result<()> {
+    let provider = Provider::start(3).await;
+
+    let report = scrape(&provider, true).await?;
+
+    assert!(report.is_complete(), "{}", report);
+    assert_eq!(report.succeeded.len(), 3);
+    assert_eq!(report.retried, 1);
+    let competitors = provider.competitors();
+    let requests = provider.statistics_requests();
+    assert_eq!(requests.len(), 4);
+    for (index, competitor) in competitors[..3].iter().enumerate() {
+        let expected = if index == 1 { 2 } else { 1 };
+        let sent = requests.iter().filter(|id| *id == competitor).count();
+        assert_eq!(sent, expected, "{}", competitor);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_strict_run_fails_on_incomplete_scrape() -> Result<()> {
+    let provider = Provider::start(3).await;
+    provider.fail_once(&provider.competitors()[1]);
+    let options = RunOptions {
+        strict: true,
+        ..Default::default()
+    };
+
+    let error = run(
+        provider.client().with_max_retries(0),
+        temp_dir("strict"),
+        &Store::new(temp_dir("strict").join("store.sqlite")),
+        engine_query(),
+        None,
+        options,
+    )
+    .await
+    .expect_err("Incomplete scrape");
+
+    assert!(error.to_string().contains("--strict"), "{}", error);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_answers_despite_incomplete_scrape() -> Result<()> {
+    let provider = Provider::start(3).await;
+    provider.fail_once(&provider.competitors()[1]);
+
+    run(
+        provider.client().with_max_retries(0),
+        temp_dir("lenient"),
+        &Store::new(temp_dir("lenient").join("store.sqlite")),
+        engine_query(),
+        None,
+        RunOptions::default(),
+    )
+    .await
+}
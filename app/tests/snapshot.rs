@@ -0,0 +1,74 @@
+use anyhow::Result;
+use app::snapshot::{Snapshot, FORMAT_VERSION};
+use chrono::{DateTime, Utc};
+use engine::repo::in_memo::InMemoRepository;
+use std::fs;
+
+mod fixture;
+use fixture::{query, season_db, temp_dir};
+
+#[test]
+fn test_write_and_read_snapshot() -> Result<()> {
+    let path = temp_dir("snapshot").join("pl-2023.snapshot");
+    let fetched_at = "2024-05-20T10:00:00Z".parse::<DateTime<Utc>>()?;
+    let db = season_db(&["Salah, Mohamed", "Nunez, Darwin"]);
+
+    Snapshot::new("sportradar", fetched_at, query(), db.clone()).write(&path)?;
+    let snapshot = Snapshot::read(&path)?;
+
+    assert_eq!(snapshot.header.format_version, FORMAT_VERSION);
+    assert_eq!(snapshot.header.provider, "sportradar");
+    assert_eq!(snapshot.header.fetched_at, fetched_at);
+    assert_eq!(snapshot.header.scope, query());
+    assert_eq!(Snapshot::read_header(&path)?, snapshot.header);
+    assert_eq!(snapshot.db.competitions.all(), db.competitions.all());
+    assert_eq!(snapshot.db.teams.all(), db.teams.all());
+    assert_eq!(snapshot.db.players.all(), db.players.all());
+    assert_eq!(snapshot.db.players_stats.all(), db.players_stats.all());
+    assert_eq!(snapshot.db.teams_stats.all(), db.teams_stats.all());
+    Ok(())
+}
+
+#[test]
+fn test_overwrite_snapshot_without_leftovers() -> Result<()> {
+    let dir = temp_dir("snapshot");
+    let path = dir.join("pl-2023.snapshot");
+    let snapshot = Snapshot::new("sportradar", Utc::now(), query(), season_db(&["Salah"]));
+    snapshot.write(&path)?;
+
+    let db = season_db(&["Salah", "Nunez"]);
+    Snapshot::new("sportradar", Utc::now(), query(), db).write(&path)?;
+
+    assert_eq!(Snapshot::read(&path)?.db.players.all().len(), 2);
+    assert_eq!(fs::read_dir(&dir)?.count(), 1, "No temporary file is left");
+    Ok(())
+}
+
+#[test]
+fn test_reject_file_without_magic_line() -> Result<()> {
+    let path = temp_dir("snapshot").join("notes.snapshot");
+    fs::write(&path, "SPORT-CLI-NOTES 1\n{}\n")?;
+
+    let error = Snapshot::read(&path).expect_err("Not a snapshot");
+
+    assert!(
+        error.to_string().starts_with("Not a sport-cli snapshot"),
+        "{}",
+        error
+    );
+    Ok(())
+}
+
+#[test]
+fn test_reject_newer_format_version() -> Result<()> {
+    let path = temp_dir("snapshot").join("future.snapshot");
+    fs::write(
+        &path,
+        format!("SPORT-CLI-SNAPSHOT {}\n{{}}\n", FORMAT_VERSION + 1),
+    )?;
+
+    let error = Snapshot::read_header(&path).expect_err("Newer format");
+
+    assert!(error.to_string().contains("upgrade sport-cli"), "{}", error);
+    Ok(())
+}
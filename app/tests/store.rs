@@ -0,0 +1,36 @@
+use anyhow::Result;
+use app::store::Store;
+use chrono::{DateTime, Utc};
+use engine::repo::in_memo::InMemoRepository;
+
+mod fixture;
+use fixture::{query, season_db, temp_dir};
+
+#[test]
+fn test_load_saved_season() -> Result<()> {
+    let store = Store::new(temp_dir("store").join("store.sqlite"));
+    let synced_at = "2024-05-20T10:00:00Z".parse::<DateTime<Utc>>()?;
+    let db = season_db(&["Salah, Mohamed", "Nunez, Darwin"]);
+
+    store.save(&db, synced_at)?;
+    let (loaded, loaded_at) = store.load(&query())?.expect("Synced season");
+
+    assert_eq!(loaded_at, Some(synced_at));
+    assert_eq!(loaded.players_stats.all(), db.players_stats.all());
+    assert_eq!(loaded.teams_stats.all(), db.teams_stats.all());
+    Ok(())
+}
+
+#[test]
+fn test_resync_drops_players_no_longer_in_the_season() -> Result<()> {
+    let store = Store::new(temp_dir("store").join("store.sqlite"));
+    store.save(&season_db(&["Salah, Mohamed", "Nunez, Darwin"]), Utc::now())?;
+
+    let resynced = season_db(&["Salah, Mohamed"]);
+    store.save(&resynced, Utc::now())?;
+    let (loaded, _) = store.load(&query())?.expect("Synced season");
+
+    assert_eq!(loaded.players_stats.all(), resynced.players_stats.all());
+    assert_eq!(loaded.players.all().len(), 1);
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use anyhow::Result;
+use app::cli::sync::run::{run, SyncOptions};
+use app::store::Store;
+use engine::repo::in_memo::InMemoRepository;
+
+mod fixture;
+use fixture::{query, temp_dir, Provider};
+
+#[tokio::test]
+async fn test_sync_season_into_store() -> Result<()> {
+    let provider = Provider::start(3).await;
+    let dir = temp_dir("sync");
+    let store = Store::new(dir.join("store.sqlite"));
+
+    run(
+        provider.client(),
+        dir.join("checkpoints"),
+        &store,
+        query(),
+        SyncOptions::default(),
+    )
+    .await?;
+
+    let (db, synced_at) = store.load(&query())?.expect("Synced season");
+    assert_eq!(db.teams_stats.all().len(), 3);
+    assert!(synced_at.is_some());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_save_nothing_from_a_partial_scrape() -> Result<()> {
+    let provider = Provider::start(3).await;
+    let dir = temp_dir("sync");
+    let store = Store::new(dir.join("store.sqlite"));
+    provider.limit_quota(2);
+
+    let error = run(
+        provider.client(),
+        dir.join("checkpoints"),
+        &store,
+        query(),
+        SyncOptions::default(),
+    )
+    .await
+    .expect_err("Partial scrape");
+
+    assert!(error.to_string().contains("--allow-partial"), "{}", error);
+    assert!(store.load(&query())?.is_none());
+
+    // Rerunning resumes the failed competitor and saves the whole season
+    provider.restore_quota();
+    run(
+        provider.client(),
+        dir.join("checkpoints"),
+        &store,
+        query(),
+        SyncOptions::default(),
+    )
+    .await?;
+    let (db, _) = store.load(&query())?.expect("Synced season");
+    assert_eq!(db.teams_stats.all().len(), 3);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_save_a_partial_scrape_when_allowed() -> Result<()> {
+    let provider = Provider::start(3).await;
+    let dir = temp_dir("sync");
+    let store = Store::new(dir.join("store.sqlite"));
+    provider.limit_quota(2);
+
+    let options = SyncOptions {
+        allow_partial: true,
+        ..Default::default()
+    };
+    run(
+        provider.client(),
+        dir.join("checkpoints"),
+        &store,
+        query(),
+        options,
+    )
+    .await?;
+
+    let (db, _) = store.load(&query())?.expect("Synced season");
+    assert_eq!(db.teams_stats.all().len(), 2);
+    Ok(())
+}
@@ -0,0 +1,189 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::engine::{MetricKind, Sort};
+use crate::filter::Predicate;
+use crate::parser;
+
+// --------------------------------------------------
+// Aggregations of player rows, such as the average goals per team
+// --------------------------------------------------
+
+/// What the player rows are grouped by. Players have no nationality until the
+/// provider models report it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupBy {
+    #[default]
+    Team,
+    Competition,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AggregateFunction {
+    #[default]
+    Sum,
+    Avg,
+    Min,
+    Max,
+    /// The number of rows the metric is reported for, or matching the condition if any.
+    Count,
+}
+
+/// An aggregate function over a metric of the grouped rows, such as `avg(goals_scored)`,
+/// or a count of the rows matching a condition, such as `count(goals_scored > 0)`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Aggregate {
+    pub function: AggregateFunction,
+    /// The metric aggregated, the first one compared by the condition of a count.
+    pub metric: MetricKind,
+    /// Leaves out the rows not matching, only for a count.
+    pub condition: Option<Predicate>,
+}
+
+impl Aggregate {
+    /// The metrics the aggregate reads, including those of its condition.
+    pub fn metrics(&self) -> Vec<&MetricKind> {
+        let mut metrics = vec![&self.metric];
+        if let Some(condition) = &self.condition {
+            metrics.extend(condition.metrics());
+        }
+        metrics
+    }
+}
+
+/// Groups the player rows of a query, after filtering them, and aggregates their metrics.
+/// The groups are ranked by each aggregate in turn, in the given order.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Aggregation {
+    pub group_by: GroupBy,
+    pub aggregates: Vec<Aggregate>,
+    pub order: Sort,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AggregateResponse<T> {
+    pub group: T,
+    /// The number of player rows in the group.
+    pub players: usize,
+    /// The values of the aggregates, in the aggregation order.
+    pub values: Vec<AggregateValue>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AggregateValue {
+    pub aggregate: Aggregate,
+    pub value: f64,
+}
+
+impl AggregateFunction {
+    /// Aggregates the values, undefined for no values but their count.
+    pub fn apply(&self, values: &[f64]) -> Option<f64> {
+        let sum = || values.iter().copied().reduce(|sum, value| sum + value);
+        match self {
+            AggregateFunction::Sum => sum(),
+            AggregateFunction::Avg => sum().map(|sum| sum / values.len() as f64),
+            AggregateFunction::Min => values.iter().copied().reduce(f64::min),
+            AggregateFunction::Max => values.iter().copied().reduce(f64::max),
+            AggregateFunction::Count => Some(values.len() as f64),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AggregateFunction::Sum => "sum",
+            AggregateFunction::Avg => "avg",
+            AggregateFunction::Min => "min",
+            AggregateFunction::Max => "max",
+            AggregateFunction::Count => "count",
+        }
+    }
+}
+
+impl FromStr for GroupBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "team" => Ok(GroupBy::Team),
+            "competition" => Ok(GroupBy::Competition),
+            _ => Err(format!(
+                "Invalid group: {}, expected team or competition",
+                s
+            )),
+        }
+    }
+}
+
+impl FromStr for AggregateFunction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "sum" => Ok(AggregateFunction::Sum),
+            "avg" => Ok(AggregateFunction::Avg),
+            "min" => Ok(AggregateFunction::Min),
+            "max" => Ok(AggregateFunction::Max),
+            "count" => Ok(AggregateFunction::Count),
+            _ => Err(format!(
+                "Invalid aggregate function: {}, expected sum, avg, min, max or count",
+                s
+            )),
+        }
+    }
+}
+
+/// Parses `function(metric)`, such as `avg(goals_scored)` or `max(assists)`,
+/// or `count(predicate)`, such as `count(goals_scored > 0)`.
+impl FromStr for Aggregate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (function, argument) = s
+            .trim()
+            .strip_suffix(')')
+            .and_then(|s| s.split_once('('))
+            .ok_or_else(|| {
+                format!(
+                    "Invalid aggregate: {}, expected function(metric) such as avg(goals_scored)",
+                    s
+                )
+            })?;
+        let function: AggregateFunction = function.trim().parse()?;
+        let argument = argument.trim();
+        if let Some(metric) = parser::metric_kind(argument) {
+            return Ok(Aggregate {
+                function,
+                metric,
+                condition: None,
+            });
+        }
+        if function != AggregateFunction::Count {
+            return Err(format!("Invalid metric: {}", argument));
+        }
+        let condition = parser::parse_predicate(argument).map_err(|e| e.to_string())?;
+        let metric = condition.metrics().first().copied().cloned().ok_or_else(|| {
+            format!(
+                "Invalid count condition: {}, expected a metric comparison such as goals_scored > 0",
+                argument
+            )
+        })?;
+        Ok(Aggregate {
+            function,
+            metric,
+            condition: Some(condition),
+        })
+    }
+}
+
+impl fmt::Display for Aggregate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.condition {
+            Some(condition) => write!(f, "{}({})", self.function.name(), condition),
+            None => write!(f, "{}({})", self.function.name(), self.metric.name()),
+        }
+    }
+}
@@ -1,85 +1,451 @@
-use chrono::NaiveDate;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Datelike, NaiveDate};
 use serde_derive::{Deserialize, Serialize};
 
+use crate::aggregate::{AggregateResponse, AggregateValue, Aggregation, GroupBy};
+use crate::filter::Predicate;
+use crate::id::{CompetitionId, CompetitorId, PlayerId};
+use crate::parser;
 use crate::repo::{
     in_memo::InMemoRepository,
-    model::{Competition, Gender, Metric, Player, PlayerDetails, PlayerStats, Team},
+    model::{
+        Competition, Gender, GroupDetails, Metric, Player, PlayerDetails, PlayerStats, Team,
+        TeamDetails, TeamMetric, TeamStats,
+    },
 };
 
 #[derive(Debug, Clone)]
 pub struct Engine;
 
 impl Engine {
+    /// Ranks the players of the queried competition season by the query sort keys, then
+    /// tiebreakers, leaving out the players the provider did not report one of the query
+    /// metrics for. Returns the queried page of the ranking.
     pub fn execute<'a>(
         &self,
-        players: &'a impl InMemoRepository<Player>,
-        teams: &'a impl InMemoRepository<Team>,
+        players: &'a impl InMemoRepository<Player, Key = PlayerId>,
+        teams: &'a impl InMemoRepository<Team, Key = CompetitorId>,
         player_stats: &'a impl InMemoRepository<PlayerStats>,
-        competitions: &'a impl InMemoRepository<Competition>,
+        competitions: &'a impl InMemoRepository<Competition, Key = CompetitionId>,
         query: &'a Query,
     ) -> Vec<QueryResponse<PlayerDetails<'a>>> {
-        let default_metric = &Metric::GoalsScored { value: 0 };
+        // The details are resolved along with the row, so ranking never looks them up again
+        let mut player_scores: Vec<(PlayerDetails, Row)> =
+            Self::season_player_stats(player_stats, competitions, query)
+                .filter_map(|player_stats| {
+                    let value = |kind: MetricKind| metric_value(player_stats, &kind);
+                    let team = teams.find(&player_stats.team_id)?;
+                    let competition = competitions.find(&player_stats.competition_id)?;
+                    let player = players.find(&player_stats.player_id);
+                    if !matches_filter(value, team, player, query) {
+                        return None;
+                    }
+                    let player_details = PlayerDetails {
+                        player_id: player_stats.player_id.as_str(),
+                        player_name: player
+                            .map(|player| player.name.as_str())
+                            .unwrap_or_default(),
+                        team_id: team.id.as_str(),
+                        team_name: &team.name,
+                        competition_id: competition.id.as_str(),
+                        competition_name: &competition.name,
+                    };
+                    Row::compute(
+                        value,
+                        player_details.player_name,
+                        player_details.player_id,
+                        query,
+                    )
+                    .map(|row| (player_details, row))
+                })
+                .collect();
+        player_scores.sort_by(|a, b| a.1.cmp(&b.1, query));
 
-        let player_scores: Vec<(&PlayerStats, &Metric)> = player_stats
-            .filter_iter(move |player_stats| {
-                competitions
-                    .all()
-                    .get(&player_stats.competition_id)
-                    .map_or(false, |competition| {
-                        competition.name == query.event
-                            && competition.location == query.location
-                            && competition.gender == query.gender
-                            && competition.season_start == query.season_start
-                            && competition.season_end == query.season_end
-                    })
+        rank(player_scores, query)
+            .into_iter()
+            .map(|(rank, player_details, row)| QueryResponse {
+                rank,
+                dimension: player_details,
+                values: row.values,
             })
-            .map(move |(_, player_stats)| {
-                let metric = match query.metric {
-                    MetricKind::GoalsScored => player_stats
-                        .metrics
-                        .iter()
-                        .find(|m| matches!(m, Metric::GoalsScored { value: _ }))
-                        .unwrap_or(default_metric),
-                    MetricKind::Assists => player_stats
-                        .metrics
-                        .iter()
-                        .find(|m| matches!(m, Metric::Assists { value: _ }))
-                        .unwrap_or(default_metric),
-                };
+            .collect()
+    }
 
-                (player_stats, metric)
+    /// Ranks the teams of the queried competition season by the query sort keys, then
+    /// tiebreakers, with metrics computed from the ones reported by the provider for the team,
+    /// or else summed over its players for the metrics adding up. Returns the queried page of
+    /// the ranking.
+    pub fn execute_teams<'a>(
+        &self,
+        teams: &'a impl InMemoRepository<Team, Key = CompetitorId>,
+        team_stats: &'a impl InMemoRepository<TeamStats>,
+        player_stats: &'a impl InMemoRepository<PlayerStats>,
+        competitions: &'a impl InMemoRepository<Competition, Key = CompetitionId>,
+        query: &'a Query,
+    ) -> Vec<QueryResponse<TeamDetails<'a>>> {
+        type TeamKey<'a> = (&'a CompetitorId, &'a CompetitionId);
+        let mut reported: HashMap<TeamKey, &TeamStats> = HashMap::new();
+        for team_stats in team_stats
+            .filter_iter(move |team_stats| {
+                Self::in_season(competitions, &team_stats.competition_id, query)
             })
-            .collect();
-
-        let mut sorted_scores = player_scores;
-        sorted_scores.sort_by(|a, b| match query.sort {
-            Sort::Asc => a.1.value().cmp(&b.1.value()),
-            Sort::Desc => b.1.value().cmp(&a.1.value()),
-        });
+            .map(|(_, team_stats)| team_stats)
+        {
+            reported.insert(
+                (&team_stats.team_id, &team_stats.competition_id),
+                team_stats,
+            );
+        }
+        let mut team_players: HashMap<TeamKey, Vec<&PlayerStats>> = HashMap::new();
+        for player_stats in Self::season_player_stats(player_stats, competitions, query) {
+            team_players
+                .entry((&player_stats.team_id, &player_stats.competition_id))
+                .or_default()
+                .push(player_stats);
+        }
 
-        sorted_scores
+        let mut team_keys: Vec<TeamKey> = reported.keys().copied().collect();
+        team_keys.extend(
+            team_players
+                .keys()
+                .filter(|key| !reported.contains_key(*key)),
+        );
+        let mut team_scores: Vec<(TeamDetails, Row)> = team_keys
             .into_iter()
-            .take(query.limit as usize)
-            .map(|(player_stats, metric)| {
-                let team = teams.find(&player_stats.team_id).unwrap();
-                let player = players.find(&player_stats.player_id).unwrap();
-                let competition = competitions.find(&player_stats.competition_id).unwrap();
-                let player_details = PlayerDetails {
-                    player_id: &player.id,
-                    player_name: &player.name,
-                    team_id: &team.id,
+            .filter_map(|key| {
+                // As reported for the team, or else summed over the players it was reported for
+                // when the players add up to the team, so ratios divide by the team matches
+                let value = |kind: MetricKind| {
+                    reported
+                        .get(&key)
+                        .and_then(|team_stats| team_metric_value(team_stats, &kind))
+                        .or_else(|| {
+                            let players = team_players.get(&key).filter(|_| kind.is_additive())?;
+                            players
+                                .iter()
+                                .filter_map(|player_stats| metric_value(player_stats, &kind))
+                                .reduce(|sum, value| sum + value)
+                        })
+                };
+                let team = teams.find(key.0)?;
+                let competition = competitions.find(key.1)?;
+                if !matches_filter(value, team, None, query) {
+                    return None;
+                }
+                let team_details = TeamDetails {
+                    team_id: team.id.as_str(),
                     team_name: &team.name,
-                    competition_id: &competition.id,
+                    team_abbreviation: &team.abbreviation,
+                    competition_id: competition.id.as_str(),
                     competition_name: &competition.name,
                 };
-                QueryResponse {
-                    dimension: player_details,
-                    metric: query.metric.clone(),
-                    value: metric.value(),
+                Row::compute(value, &team.name, team.id.as_str(), query)
+                    .map(|row| (team_details, row))
+            })
+            .collect();
+        team_scores.sort_by(|a, b| a.1.cmp(&b.1, query));
+
+        rank(team_scores, query)
+            .into_iter()
+            .map(|(rank, team_details, row)| QueryResponse {
+                rank,
+                dimension: team_details,
+                values: row.values,
+            })
+            .collect()
+    }
+
+    /// Groups the players of the queried competition season, after filtering them, and
+    /// ranks the groups by their aggregates, then name. The query metrics, sort keys and
+    /// tiebreakers are not used.
+    pub fn aggregate<'a>(
+        &self,
+        players: &'a impl InMemoRepository<Player, Key = PlayerId>,
+        teams: &'a impl InMemoRepository<Team, Key = CompetitorId>,
+        player_stats: &'a impl InMemoRepository<PlayerStats>,
+        competitions: &'a impl InMemoRepository<Competition, Key = CompetitionId>,
+        query: &'a Query,
+        aggregation: &Aggregation,
+    ) -> Vec<AggregateResponse<GroupDetails<'a>>> {
+        type GroupRow<'a> = (&'a PlayerStats, &'a Team, Option<&'a Player>);
+        let mut groups: HashMap<&str, (GroupDetails, Vec<GroupRow>)> = HashMap::new();
+        for player_stats in Self::season_player_stats(player_stats, competitions, query) {
+            let value = |kind: MetricKind| metric_value(player_stats, &kind);
+            let Some(team) = teams.find(&player_stats.team_id) else {
+                continue;
+            };
+            let player = players.find(&player_stats.player_id);
+            if !matches_filter(value, team, player, query) || !has_min_matches(value, query) {
+                continue;
+            }
+            let group = match aggregation.group_by {
+                GroupBy::Team => GroupDetails {
+                    group_id: team.id.as_str(),
+                    group_name: &team.name,
+                },
+                GroupBy::Competition => {
+                    let Some(competition) = competitions.find(&player_stats.competition_id) else {
+                        continue;
+                    };
+                    GroupDetails {
+                        group_id: competition.id.as_str(),
+                        group_name: &competition.name,
+                    }
                 }
+            };
+            groups
+                .entry(group.group_id)
+                .or_insert_with(|| (group, vec![]))
+                .1
+                .push((player_stats, team, player));
+        }
+
+        let mut results: Vec<AggregateResponse<GroupDetails>> = groups
+            .into_values()
+            .filter_map(|(group, rows)| {
+                let values = aggregation
+                    .aggregates
+                    .iter()
+                    .map(|aggregate| {
+                        // The rows the provider did not report the metric for are left out,
+                        // as are those not matching the condition of the aggregate
+                        let values: Vec<f64> = rows
+                            .iter()
+                            .filter_map(|(player_stats, team, player)| {
+                                let value = |kind: MetricKind| metric_value(player_stats, &kind);
+                                let matches =
+                                    aggregate.condition.as_ref().is_none_or(|condition| {
+                                        condition.matches(value, team, *player)
+                                    });
+                                matches.then(|| aggregate.metric.compute(value)).flatten()
+                            })
+                            .collect();
+                        aggregate
+                            .function
+                            .apply(&values)
+                            .map(|value| AggregateValue {
+                                aggregate: aggregate.clone(),
+                                value,
+                            })
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Some(AggregateResponse {
+                    group,
+                    players: rows.len(),
+                    values,
+                })
             })
+            .collect();
+        results.sort_by(|a, b| {
+            a.values
+                .iter()
+                .zip(&b.values)
+                .map(|(a, b)| match aggregation.order {
+                    Sort::Asc => a.value.total_cmp(&b.value),
+                    Sort::Desc => b.value.total_cmp(&a.value),
+                })
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| a.group.group_name.cmp(b.group.group_name))
+                .then_with(|| a.group.group_id.cmp(b.group.group_id))
+        });
+        results
+            .into_iter()
+            .skip(query.offset as usize)
+            .take(query.limit as usize)
             .collect()
     }
+
+    /// The player statistics of the queried competition season.
+    fn season_player_stats<'a>(
+        player_stats: &'a impl InMemoRepository<PlayerStats>,
+        competitions: &'a impl InMemoRepository<Competition, Key = CompetitionId>,
+        query: &'a Query,
+    ) -> impl Iterator<Item = &'a PlayerStats> {
+        player_stats
+            .filter_iter(move |player_stats| {
+                Self::in_season(competitions, &player_stats.competition_id, query)
+            })
+            .map(|(_, player_stats)| player_stats)
+    }
+
+    /// Whether the competition is the queried competition, its season lying within
+    /// the queried dates.
+    fn in_season(
+        competitions: &impl InMemoRepository<Competition, Key = CompetitionId>,
+        competition_id: &CompetitionId,
+        query: &Query,
+    ) -> bool {
+        competitions
+            .all()
+            .get(competition_id)
+            .is_some_and(|competition| {
+                competition.name == query.event
+                    && competition.location == query.location
+                    && competition.gender == query.gender
+                    && query.season_start <= competition.season_start
+                    && competition.season_end <= query.season_end
+            })
+    }
+}
+
+/// The value of the metric in the player statistics, if the provider reported it.
+fn metric_value(player_stats: &PlayerStats, kind: &MetricKind) -> Option<f64> {
+    player_stats
+        .metrics
+        .iter()
+        .find(|metric| metric.name() == kind.name())
+        .map(|metric| metric.value() as f64)
+}
+
+/// The metric values of a ranked row, along with the values of its sort keys and
+/// tiebreakers, and its name and id to break the remaining ties.
+struct Row {
+    values: Vec<MetricValue>,
+    keys: Vec<f64>,
+    /// The values of the metric tiebreakers, in the query order.
+    ties: Vec<Option<f64>>,
+    name: String,
+    id: String,
+}
+
+impl Row {
+    /// Computes the query metrics, sort keys and tiebreakers of a row, given its metric values.
+    /// None when the row is left out, as one of its metrics or sort keys is undefined.
+    fn compute(
+        value: impl Fn(MetricKind) -> Option<f64> + Copy,
+        name: &str,
+        id: &str,
+        query: &Query,
+    ) -> Option<Row> {
+        if !has_min_matches(value, query) {
+            return None;
+        }
+        let values = query
+            .metrics
+            .iter()
+            .map(|metric| {
+                metric.compute(value).map(|value| MetricValue {
+                    metric: metric.clone(),
+                    value,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let keys = query
+            .sort
+            .iter()
+            .map(|key| key.metric.compute(value))
+            .collect::<Option<Vec<_>>>()?;
+        let ties = query
+            .tiebreakers
+            .iter()
+            .filter_map(|tiebreaker| match tiebreaker {
+                Tiebreaker::Metric(key) => Some(key.metric.compute(value)),
+                Tiebreaker::Name => None,
+            })
+            .collect();
+        Some(Row {
+            values,
+            keys,
+            ties,
+            name: name.to_string(),
+            id: id.to_string(),
+        })
+    }
+
+    /// Orders the rows by each sort key and tiebreaker in turn, then by id, so that rows
+    /// come in the same order between runs.
+    fn cmp(&self, other: &Row, query: &Query) -> Ordering {
+        self.cmp_ranks(other, query)
+            .then_with(|| self.id.cmp(&other.id))
+    }
+
+    /// Orders the rows by each sort key and tiebreaker in turn, equal when they tie
+    /// and share a rank.
+    fn cmp_ranks(&self, other: &Row, query: &Query) -> Ordering {
+        let order = |order: &Sort, ordering: Ordering| match order {
+            Sort::Asc => ordering,
+            Sort::Desc => ordering.reverse(),
+        };
+        let keys = query
+            .sort
+            .iter()
+            .zip(self.keys.iter().zip(&other.keys))
+            .map(|(key, (a, b))| order(&key.order, a.total_cmp(b)));
+        let mut ties = self.ties.iter().zip(&other.ties);
+        let tiebreakers = query
+            .tiebreakers
+            .iter()
+            .map(move |tiebreaker| match tiebreaker {
+                Tiebreaker::Metric(key) => match ties.next() {
+                    // Rows without the metric come last, whatever the order
+                    Some((Some(a), Some(b))) => order(&key.order, a.total_cmp(b)),
+                    Some((Some(_), None)) => Ordering::Less,
+                    Some((None, Some(_))) => Ordering::Greater,
+                    _ => Ordering::Equal,
+                },
+                Tiebreaker::Name => self.name.to_lowercase().cmp(&other.name.to_lowercase()),
+            });
+        keys.chain(tiebreakers)
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+/// Ranks the ordered rows, then keeps the queried page of them.
+fn rank<T>(rows: Vec<(T, Row)>, query: &Query) -> Vec<(u32, T, Row)> {
+    let mut ranks: Vec<u32> = Vec::with_capacity(rows.len());
+    for (index, (_, row)) in rows.iter().enumerate() {
+        let previous = index
+            .checked_sub(1)
+            .map(|previous| (&rows[previous].1, ranks[previous]));
+        let rank = match (&query.ranking, previous) {
+            (Ranking::Ordinal, _) | (_, None) => index as u32 + 1,
+            (_, Some((previous, rank))) if previous.cmp_ranks(row, query).is_eq() => rank,
+            (Ranking::Standard, _) => index as u32 + 1,
+            (Ranking::Dense, Some((_, rank))) => rank + 1,
+        };
+        ranks.push(rank);
+    }
+    rows.into_iter()
+        .zip(ranks)
+        .skip(query.offset as usize)
+        .take(query.limit as usize)
+        .map(|((item, row), rank)| (rank, item, row))
+        .collect()
+}
+
+/// Whether the row matches the filter of the query, if any.
+fn matches_filter(
+    value: impl Fn(MetricKind) -> Option<f64> + Copy,
+    team: &Team,
+    player: Option<&Player>,
+    query: &Query,
+) -> bool {
+    query
+        .filter
+        .as_ref()
+        .is_none_or(|filter| filter.matches(value, team, player))
+}
+
+/// Whether the row played the minimum number of matches of the query, if any.
+fn has_min_matches(value: impl Fn(MetricKind) -> Option<f64>, query: &Query) -> bool {
+    query.min_matches == 0
+        || value(MetricKind::MatchesPlayed)
+            .is_some_and(|matches| matches >= query.min_matches as f64)
+}
+
+/// The value of the metric in the team statistics, if the provider reported it.
+fn team_metric_value(team_stats: &TeamStats, kind: &MetricKind) -> Option<f64> {
+    team_stats
+        .metrics
+        .iter()
+        .find(|metric| metric.name() == kind.name())
+        .map(TeamMetric::value)
 }
 
 // --------------------------------------------------
@@ -91,7 +457,6 @@ impl Engine {
 pub enum Dimension {
     #[default]
     Player,
-    // TODO! support other dimensions
     Team,
 }
 
@@ -103,36 +468,396 @@ pub enum Sort {
     Desc,
 }
 
+/// Orders the rows of a query by a metric, the first key deciding unless it ties.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SortKey {
+    pub metric: MetricKind,
+    pub order: Sort,
+}
+
+/// Orders the rows tied on every sort key, such as the fewer matches played first.
+/// The rows still tied share a rank, and come ordered by id.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tiebreaker {
+    Metric(SortKey),
+    /// The player (or team) name, alphabetically and ignoring case.
+    Name,
+}
+
+/// How tied rows are ranked.
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
-pub enum MetricKind {
+pub enum Ranking {
+    /// Tied rows share a rank, and the next rank skips them: 1, 2, 2, 4.
+    #[default]
+    Standard,
+    /// Tied rows share a rank, and the next rank follows: 1, 2, 2, 3.
+    Dense,
+    /// Every row has its own rank, in order: 1, 2, 3, 4.
+    Ordinal,
+}
+
+impl Tiebreaker {
+    pub fn name(&self) -> String {
+        match self {
+            Tiebreaker::Metric(SortKey {
+                metric,
+                order: Sort::Asc,
+            }) => format!("{}:asc", metric.name()),
+            Tiebreaker::Metric(SortKey {
+                metric,
+                order: Sort::Desc,
+            }) => format!("{}:desc", metric.name()),
+            Tiebreaker::Name => "name".to_string(),
+        }
+    }
+}
+
+/// Parses `name`, `metric:asc`, `metric:desc`, or a metric alone for descending order.
+impl FromStr for Tiebreaker {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("name") {
+            return Ok(Tiebreaker::Name);
+        }
+        let (metric, order) = s.split_once(':').unwrap_or((s, "desc"));
+        let order = match order.to_lowercase().as_str() {
+            "asc" => Sort::Asc,
+            "desc" => Sort::Desc,
+            _ => {
+                return Err(format!(
+                    "Invalid tiebreaker order: {}, expected asc or desc",
+                    order
+                ))
+            }
+        };
+        let metric = parser::metric_kind(metric).ok_or_else(|| {
+            format!(
+                "Invalid tiebreaker: {}, expected name or a metric such as matches_played:asc",
+                s
+            )
+        })?;
+        Ok(Tiebreaker::Metric(SortKey { metric, order }))
+    }
+}
+
+impl FromStr for Ranking {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "standard" => Ok(Ranking::Standard),
+            "dense" => Ok(Ranking::Dense),
+            "ordinal" => Ok(Ranking::Ordinal),
+            _ => Err(format!(
+                "Invalid ranking: {}, expected standard, dense or ordinal",
+                s
+            )),
+        }
+    }
+}
+
+impl Ranking {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Ranking::Standard => "standard",
+            Ranking::Dense => "dense",
+            Ranking::Ordinal => "ordinal",
+        }
+    }
+}
+
+/// Declares the metrics a query can rank by, with their names as typed and serialized.
+macro_rules! metric_kinds {
+    ($($(#[$meta:meta])* $variant:ident => $name:literal),+ $(,)?) => {
+        #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+        pub enum MetricKind {
+            $(
+                $(#[$meta])*
+                #[serde(rename = $name)]
+                $variant,
+            )+
+        }
+
+        impl MetricKind {
+            /// Every metric, in declaration order.
+            pub const ALL: &'static [MetricKind] = &[$(MetricKind::$variant,)+];
+
+            /// The metric name, such as "goals_scored".
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(MetricKind::$variant => $name,)+
+                }
+            }
+        }
+
+        impl FromStr for MetricKind {
+            type Err = String;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s.to_lowercase().as_str() {
+                    $($name => Ok(MetricKind::$variant),)+
+                    _ => Err(format!(
+                        "Invalid metric: {}, expected one of {}",
+                        s,
+                        [$($name),+].join(", ")
+                    )),
+                }
+            }
+        }
+    };
+}
+
+metric_kinds!(
     #[default]
-    #[serde(rename = "goals_scored")]
-    GoalsScored,
-    Assists,
-    // TODO! support other metrics
+    GoalsScored => "goals_scored",
+    Assists => "assists",
+    AverageBallPossession => "average_ball_possession",
+    CardsGiven => "cards_given",
+    CornerKicks => "corner_kicks",
+    FreeKicks => "free_kicks",
+    GoalsByFoot => "goals_by_foot",
+    GoalsByHead => "goals_by_head",
+    GoalsByPenalty => "goals_by_penalty",
+    GoalsConceded => "goals_conceded",
+    GoalsConcededFirstHalf => "goals_conceded_first_half",
+    GoalsConcededSecondHalf => "goals_conceded_second_half",
+    GoalsScoredFirstHalf => "goals_scored_first_half",
+    GoalsScoredSecondHalf => "goals_scored_second_half",
+    MatchesPlayed => "matches_played",
+    Offsides => "offsides",
+    OwnGoals => "own_goals",
+    PenaltiesMissed => "penalties_missed",
+    RedCards => "red_cards",
+    ShotsBlocked => "shots_blocked",
+    ShotsOffTarget => "shots_off_target",
+    ShotsOnBar => "shots_on_bar",
+    ShotsOnPost => "shots_on_post",
+    ShotsOnTarget => "shots_on_target",
+    ShotsTotal => "shots_total",
+    SubstitutedIn => "substituted_in",
+    SubstitutedOut => "substituted_out",
+    YellowCards => "yellow_cards",
+    YellowRedCards => "yellow_red_cards",
+    /// Goals scored plus assists.
+    GoalContributions => "goal_contributions",
+    /// Goals scored per match played.
+    GoalsPerMatch => "goals_per_match",
+    /// The share of penalties taken that were scored, from 0 to 1.
+    PenaltyConversion => "penalty_conversion",
+    /// Yellow, second yellow and red cards per match played.
+    CardsPerMatch => "cards_per_match",
+    /// The share of goals scored by head, from 0 to 1.
+    HeaderShare => "header_share",
+);
+
+impl MetricKind {
+    /// Whether the metric is computed from other metrics, such as goals per match.
+    pub fn is_derived(&self) -> bool {
+        matches!(
+            self,
+            MetricKind::GoalContributions
+                | MetricKind::GoalsPerMatch
+                | MetricKind::PenaltyConversion
+                | MetricKind::CardsPerMatch
+                | MetricKind::HeaderShare
+        )
+    }
+
+    /// Whether the team value is the sum of the values of its players, such as goals, unlike
+    /// matches played, where the players of a match each count it, or derived ratios.
+    pub fn is_additive(&self) -> bool {
+        !self.is_derived() && *self != MetricKind::MatchesPlayed
+    }
+
+    /// Whether players have the metric, as opposed to team only metrics such as possession.
+    pub fn is_player_metric(&self) -> bool {
+        self.is_derived() || Metric::from_name(self.name(), 0).is_some()
+    }
+
+    /// Whether the provider reports the metric per team.
+    pub fn is_team_metric(&self) -> bool {
+        TeamMetric::from_name(self.name(), 0.0).is_some()
+    }
+
+    /// The value of the metric, given the values of the metrics of a row. Undefined when
+    /// a metric it is computed from is missing, or when dividing by zero.
+    pub fn compute(&self, value: impl Fn(MetricKind) -> Option<f64>) -> Option<f64> {
+        let ratio =
+            |numerator: f64, denominator: f64| (denominator > 0.0).then(|| numerator / denominator);
+        match self {
+            MetricKind::GoalContributions => {
+                Some(value(MetricKind::GoalsScored)? + value(MetricKind::Assists)?)
+            }
+            MetricKind::GoalsPerMatch => ratio(
+                value(MetricKind::GoalsScored)?,
+                value(MetricKind::MatchesPlayed)?,
+            ),
+            MetricKind::PenaltyConversion => {
+                let scored = value(MetricKind::GoalsByPenalty)?;
+                ratio(scored, scored + value(MetricKind::PenaltiesMissed)?)
+            }
+            MetricKind::CardsPerMatch => ratio(
+                value(MetricKind::YellowCards)?
+                    + value(MetricKind::YellowRedCards)?
+                    + value(MetricKind::RedCards)?,
+                value(MetricKind::MatchesPlayed)?,
+            ),
+            MetricKind::HeaderShare => ratio(
+                value(MetricKind::GoalsByHead)?,
+                value(MetricKind::GoalsScored)?,
+            ),
+            kind => value(kind.clone()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Query {
     pub event: String,
     pub location: String,
+    /// The dates the season lies within, either exactly or a window such as
+    /// 2023-07-01 to 2024-06-30 for the 2023/24 season.
     pub season_start: NaiveDate,
     pub season_end: NaiveDate,
     pub gender: Gender,
     pub dimension: Dimension,
-    // TODO! support multiple metrics
-    pub metric: MetricKind,
-    // TODO! support sorting by multiple fields
-    pub sort: Sort,
-    pub limit: u8,
+    /// The metrics returned for each row.
+    pub metrics: Vec<MetricKind>,
+    /// The keys the rows are ordered by, which need not be returned metrics.
+    pub sort: Vec<SortKey>,
+    /// Order the rows tied on every sort key.
+    pub tiebreakers: Vec<Tiebreaker>,
+    pub ranking: Ranking,
+    /// The number of ranked rows skipped, before returning at most `limit` of them.
+    pub offset: u32,
+    pub limit: u32,
+    /// Leaves out the rows that played fewer matches, so ratios are not dominated
+    /// by a few games. Zero keeps every row.
+    pub min_matches: u32,
+    /// Leaves out the rows not matching, before ranking them.
+    pub filter: Option<Predicate>,
+}
+
+impl FromStr for Query {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parser::parse_query(s).map_err(|e| e.to_string())
+    }
+}
+
+/// Writes the query back in the syntax it is parsed from, such as
+/// `top 10 players by goal_contributions in "Premier League" England 2023/24`.
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let order = self.sort.first().map(|key| &key.order);
+        let rank = if order == Some(&Sort::Asc) {
+            "bottom"
+        } else {
+            "top"
+        };
+        let dimension = match self.dimension {
+            Dimension::Player => "players",
+            Dimension::Team => "teams",
+        };
+        write!(f, "{} {} {}", rank, self.limit, dimension)?;
+
+        let names = |metrics: &[MetricKind]| {
+            metrics
+                .iter()
+                .map(|metric| metric.name())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let key = |key: &SortKey| match &key.order {
+            key_order if key_order == order.unwrap_or(&Sort::Desc) => key.metric.name().to_string(),
+            Sort::Asc => format!("{} asc", key.metric.name()),
+            Sort::Desc => format!("{} desc", key.metric.name()),
+        };
+        if !self.sort.is_empty() {
+            let keys = self.sort.iter().map(key).collect::<Vec<_>>();
+            write!(f, " by {}", keys.join(", "))?;
+        }
+        if !self.tiebreakers.is_empty() {
+            let tiebreakers = self
+                .tiebreakers
+                .iter()
+                .map(|tiebreaker| match tiebreaker {
+                    Tiebreaker::Metric(sort_key) => key(sort_key),
+                    Tiebreaker::Name => "name".to_string(),
+                })
+                .collect::<Vec<_>>();
+            write!(f, " ties by {}", tiebreakers.join(", "))?;
+        }
+        if self.sort.is_empty() || self.metrics != parser::sort_metrics(&self.sort) {
+            write!(f, " show {}", names(&self.metrics))?;
+        }
+
+        let text = |text: &str| {
+            let bare = text.starts_with(|c: char| c.is_alphabetic())
+                && text.chars().all(|c| c.is_alphanumeric() || c == '_');
+            if bare {
+                text.to_string()
+            } else {
+                parser::quote(text)
+            }
+        };
+        write!(f, " in {} {} ", text(&self.event), text(&self.location))?;
+        let season = Some((self.season_start, self.season_end));
+        let year = self.season_start.year();
+        if season == parser::season_years(year) {
+            write!(f, "{}/{:02}", year, (year + 1) % 100)?;
+        } else if season == parser::calendar_year(year) {
+            write!(f, "{}", year)?;
+        } else {
+            write!(f, "{}..{}", self.season_start, self.season_end)?;
+        }
+        if self.gender == Gender::Female {
+            f.write_str(" female")?;
+        }
+
+        if self.min_matches > 0 {
+            write!(f, " min {} matches", self.min_matches)?;
+        }
+        if self.offset > 0 {
+            write!(f, " offset {}", self.offset)?;
+        }
+        if self.ranking != Ranking::default() {
+            write!(f, " rank {}", self.ranking.name())?;
+        }
+        if let Some(filter) = &self.filter {
+            write!(f, " where {}", filter)?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct QueryResponse<T> {
+    /// The rank of the row, counted from 1 before skipping the query offset.
+    pub rank: u32,
     pub dimension: T,
+    /// The values of the query metrics, in the query order.
+    pub values: Vec<MetricValue>,
+}
+
+impl<T> QueryResponse<T> {
+    /// The value of the given metric, if the query returns it.
+    pub fn value(&self, metric: &MetricKind) -> Option<f64> {
+        self.values
+            .iter()
+            .find(|value| &value.metric == metric)
+            .map(|value| value.value)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetricValue {
     pub metric: MetricKind,
-    pub value: u32,
+    pub value: f64,
 }
 unsafe impl<T> Send for QueryResponse<T> {}
 unsafe impl<T> Sync for QueryResponse<T> {}
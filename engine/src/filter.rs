@@ -0,0 +1,220 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::engine::MetricKind;
+use crate::parser;
+use crate::repo::model::{Player, Team};
+
+// --------------------------------------------------
+// Filter predicates, such as `goals_scored >= 10 and team = LIV`
+// --------------------------------------------------
+
+/// A condition on the rows of a query, evaluated before ranking them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Predicate {
+    /// Compares a metric of the row, false when the metric is not reported.
+    Metric {
+        metric: MetricKind,
+        op: Comparison,
+        value: f64,
+    },
+    /// Matches the team id, name or abbreviation, ignoring case.
+    Team {
+        op: TextComparison,
+        value: String,
+    },
+    /// Matches the player name, ignoring case. False for team rows.
+    Player {
+        op: TextComparison,
+        value: String,
+    },
+    And(Box<Predicate>, Box<Predicate>),
+    Or(Box<Predicate>, Box<Predicate>),
+    Not(Box<Predicate>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TextComparison {
+    Eq,
+    Ne,
+    Contains,
+}
+
+impl Predicate {
+    /// Whether the row matches, given its metric values, team and player (for player rows).
+    pub fn matches(
+        &self,
+        value: impl Fn(MetricKind) -> Option<f64> + Copy,
+        team: &Team,
+        player: Option<&Player>,
+    ) -> bool {
+        match self {
+            Predicate::Metric {
+                metric,
+                op,
+                value: expected,
+            } => metric
+                .compute(value)
+                .is_some_and(|actual| op.compare(actual, *expected)),
+            Predicate::Team { op, value } => {
+                let names = [team.id.as_str(), &team.name, &team.abbreviation];
+                match op {
+                    TextComparison::Ne => !names.iter().any(|name| eq_ignore_case(name, value)),
+                    _ => names.iter().any(|name| op.compare(name, value)),
+                }
+            }
+            Predicate::Player { op, value } => {
+                player.is_some_and(|player| op.compare(&player.name, value))
+            }
+            Predicate::And(left, right) => {
+                left.matches(value, team, player) && right.matches(value, team, player)
+            }
+            Predicate::Or(left, right) => {
+                left.matches(value, team, player) || right.matches(value, team, player)
+            }
+            Predicate::Not(predicate) => !predicate.matches(value, team, player),
+        }
+    }
+
+    /// The metrics the predicate compares.
+    pub fn metrics(&self) -> Vec<&MetricKind> {
+        match self {
+            Predicate::Metric { metric, .. } => vec![metric],
+            Predicate::Team { .. } | Predicate::Player { .. } => vec![],
+            Predicate::And(left, right) | Predicate::Or(left, right) => {
+                let mut metrics = left.metrics();
+                metrics.extend(right.metrics());
+                metrics
+            }
+            Predicate::Not(predicate) => predicate.metrics(),
+        }
+    }
+
+    /// Whether the predicate matches player names, which only player rows have.
+    pub fn is_about_players(&self) -> bool {
+        match self {
+            Predicate::Player { .. } => true,
+            Predicate::Metric { .. } | Predicate::Team { .. } => false,
+            Predicate::And(left, right) | Predicate::Or(left, right) => {
+                left.is_about_players() || right.is_about_players()
+            }
+            Predicate::Not(predicate) => predicate.is_about_players(),
+        }
+    }
+
+    /// Binding strength, as to parenthesize the operands that bind looser.
+    fn precedence(&self) -> u8 {
+        match self {
+            Predicate::Or(..) => 1,
+            Predicate::And(..) => 2,
+            _ => 3,
+        }
+    }
+}
+
+impl Comparison {
+    pub fn compare(&self, actual: f64, expected: f64) -> bool {
+        match self {
+            Comparison::Eq => actual == expected,
+            Comparison::Ne => actual != expected,
+            Comparison::Lt => actual < expected,
+            Comparison::Le => actual <= expected,
+            Comparison::Gt => actual > expected,
+            Comparison::Ge => actual >= expected,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Comparison::Eq => "=",
+            Comparison::Ne => "!=",
+            Comparison::Lt => "<",
+            Comparison::Le => "<=",
+            Comparison::Gt => ">",
+            Comparison::Ge => ">=",
+        }
+    }
+}
+
+impl TextComparison {
+    pub fn compare(&self, actual: &str, expected: &str) -> bool {
+        match self {
+            TextComparison::Eq => eq_ignore_case(actual, expected),
+            TextComparison::Ne => !eq_ignore_case(actual, expected),
+            TextComparison::Contains => actual.to_lowercase().contains(&expected.to_lowercase()),
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            TextComparison::Eq => "=",
+            TextComparison::Ne => "!=",
+            TextComparison::Contains => "~",
+        }
+    }
+}
+
+fn eq_ignore_case(actual: &str, expected: &str) -> bool {
+    actual.to_lowercase() == expected.to_lowercase()
+}
+
+impl FromStr for Predicate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parser::parse_predicate(s).map_err(|e| e.to_string())
+    }
+}
+
+/// Writes the predicate back in the syntax it is parsed from.
+impl fmt::Display for Predicate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let operand = |f: &mut fmt::Formatter, operand: &Predicate, precedence: u8| {
+            if operand.precedence() < precedence {
+                write!(f, "({})", operand)
+            } else {
+                write!(f, "{}", operand)
+            }
+        };
+        match self {
+            Predicate::Metric { metric, op, value } => {
+                write!(f, "{} {} {}", metric.name(), op.symbol(), value)
+            }
+            Predicate::Team { op, value } => {
+                write!(f, "team {} {}", op.symbol(), parser::quote(value))
+            }
+            Predicate::Player { op, value } => {
+                write!(f, "player {} {}", op.symbol(), parser::quote(value))
+            }
+            Predicate::And(left, right) => {
+                operand(f, left, 2)?;
+                f.write_str(" and ")?;
+                operand(f, right, 3)
+            }
+            Predicate::Or(left, right) => {
+                operand(f, left, 1)?;
+                f.write_str(" or ")?;
+                operand(f, right, 2)
+            }
+            Predicate::Not(predicate) => {
+                f.write_str("not ")?;
+                operand(f, predicate, 3)
+            }
+        }
+    }
+}
@@ -1,4 +1,9 @@
+pub mod aggregate;
 pub mod engine;
+pub mod filter;
+// The urn ids live in their own crate, shared with the provider client
+pub use ::id;
+pub mod parser;
 
 pub mod repo {
     pub mod competitions;
@@ -6,5 +11,8 @@ pub mod repo {
     pub mod model;
     pub mod player_stats;
     pub mod players;
+    pub mod sqlite;
+    pub mod storage;
+    pub mod team_stats;
     pub mod teams;
 }
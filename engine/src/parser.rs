@@ -0,0 +1,768 @@
+use std::error::Error;
+use std::fmt;
+
+use chrono::NaiveDate;
+
+use crate::engine::{Dimension, MetricKind, Query, Ranking, Sort, SortKey, Tiebreaker};
+use crate::filter::{Comparison, Predicate, TextComparison};
+use crate::repo::model::Gender;
+
+// --------------------------------------------------
+// Tokens
+// --------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// A word, such as a field, a keyword, a bare text value or a season such as `2023/24`.
+    Ident(String),
+    Number(f64),
+    /// A quoted text value.
+    Str(String),
+    /// A comparison operator, such as `>=`.
+    Op(&'static str),
+    LParen,
+    RParen,
+    Comma,
+    End,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::Ident(word) => write!(f, "`{}`", word),
+            Token::Number(number) => write!(f, "`{}`", number),
+            Token::Str(text) => f.write_str(&quote(text)),
+            Token::Op(op) => write!(f, "`{}`", op),
+            Token::LParen => f.write_str("`(`"),
+            Token::RParen => f.write_str("`)`"),
+            Token::Comma => f.write_str("`,`"),
+            Token::End => f.write_str("the end of input"),
+        }
+    }
+}
+
+/// A token along with its byte range in the input.
+#[derive(Debug, Clone)]
+struct Spanned {
+    token: Token,
+    start: usize,
+    end: usize,
+}
+
+const OPS: &[&str] = &["<=", ">=", "!=", "=", "<", ">", "~"];
+
+fn tokenize(input: &str) -> Result<Vec<Spanned>, ParseError> {
+    let mut tokens = vec![];
+    let mut chars = input.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '(' || c == ')' || c == ',' {
+            chars.next();
+            let token = match c {
+                '(' => Token::LParen,
+                ')' => Token::RParen,
+                _ => Token::Comma,
+            };
+            tokens.push(Spanned {
+                token,
+                start,
+                end: start + 1,
+            });
+        } else if c == '"' || c == '\'' {
+            chars.next();
+            let mut text = String::new();
+            let mut end = None;
+            while let Some((index, next)) = chars.next() {
+                match next {
+                    '\\' => {
+                        if let Some((_, escaped)) = chars.next() {
+                            text.push(escaped);
+                        }
+                    }
+                    _ if next == c => {
+                        end = Some(index + 1);
+                        break;
+                    }
+                    _ => text.push(next),
+                }
+            }
+            let end = end.ok_or_else(|| {
+                ParseError::new(
+                    "Unterminated text, missing its closing quote",
+                    input,
+                    start,
+                    input.len(),
+                )
+            })?;
+            tokens.push(Spanned {
+                token: Token::Str(text),
+                start,
+                end,
+            });
+        } else if let Some(op) = OPS.iter().find(|op| input[start..].starts_with(**op)) {
+            for _ in 0..op.len() {
+                chars.next();
+            }
+            tokens.push(Spanned {
+                token: Token::Op(op),
+                start,
+                end: start + op.len(),
+            });
+        } else if c.is_ascii_digit()
+            || c == '.'
+            || (c == '-'
+                && input[start + 1..].starts_with(|n: char| n.is_ascii_digit() || n == '.'))
+        {
+            // the sign of a negative number, such as -0.5
+            let sign = usize::from(c == '-');
+            let mut end = start;
+            while let Some(&(index, next)) = chars.peek() {
+                if !(next.is_ascii_digit() || next == '.' || next == '/' || next == '-') {
+                    break;
+                }
+                end = index + next.len_utf8();
+                chars.next();
+            }
+            // seasons and dates, such as 2023/24 or 2023-08-11..2024-05-19
+            if input[start + sign..end].contains(['/', '-']) {
+                tokens.push(Spanned {
+                    token: Token::Ident(input[start..end].to_string()),
+                    start,
+                    end,
+                });
+                continue;
+            }
+            let number = input[start..end].parse().map_err(|_| {
+                ParseError::new(
+                    format!("Invalid number `{}`", &input[start..end]),
+                    input,
+                    start,
+                    end,
+                )
+            })?;
+            tokens.push(Spanned {
+                token: Token::Number(number),
+                start,
+                end,
+            });
+        } else if c.is_alphanumeric() || c == '_' {
+            let mut end = start;
+            while let Some(&(index, next)) = chars.peek() {
+                if !(next.is_alphanumeric() || next == '_' || next == ':' || next == '+') {
+                    break;
+                }
+                end = index + next.len_utf8();
+                chars.next();
+            }
+            tokens.push(Spanned {
+                token: Token::Ident(input[start..end].to_string()),
+                start,
+                end,
+            });
+        } else {
+            return Err(ParseError::new(
+                format!("Unexpected character `{}`", c),
+                input,
+                start,
+                start + c.len_utf8(),
+            ));
+        }
+    }
+    tokens.push(Spanned {
+        token: Token::End,
+        start: input.len(),
+        end: input.len(),
+    });
+    Ok(tokens)
+}
+
+/// Quotes the text the way the tokenizer reads it back, escaping its quotes and backslashes.
+pub(crate) fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        if c == '"' || c == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+// --------------------------------------------------
+// Errors
+// --------------------------------------------------
+
+/// Why an input could not be parsed, pointing at the offending token.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    pub input: String,
+    /// The byte range of the offending token in the input.
+    pub start: usize,
+    pub end: usize,
+}
+
+impl ParseError {
+    fn new(message: impl Into<String>, input: &str, start: usize, end: usize) -> Self {
+        ParseError {
+            message: message.into(),
+            input: input.to_string(),
+            start,
+            end,
+        }
+    }
+}
+
+/// The message, then the input with the offending token underlined.
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let offset = self.input[..self.start].chars().count();
+        let width = self.input[self.start..self.end].chars().count().max(1);
+        write!(
+            f,
+            "{}\n  {}\n  {}{}",
+            self.message,
+            self.input,
+            " ".repeat(offset),
+            "^".repeat(width)
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+// --------------------------------------------------
+// Parser
+// --------------------------------------------------
+
+/// Parses a filter predicate, such as `goals_scored >= 10 and team = LIV`.
+///
+/// Comparisons are combined with `and`, `or` and `not`, from the loosest to the tightest
+/// binding, and grouped with parentheses. They compare a metric with a number
+/// (`=`, `!=`, `<`, `<=`, `>`, `>=`), or the `team` or the `player` with a text (`=`, `!=`,
+/// or `~` for contains), quoted when it has spaces. Within quotes, a backslash escapes the
+/// next character, such as a quote or another backslash.
+pub fn parse_predicate(input: &str) -> Result<Predicate, ParseError> {
+    let mut parser = Parser::new(input)?;
+    let predicate = parser.predicate()?;
+    parser.expect_end("`and`, `or`")?;
+    Ok(predicate)
+}
+
+/// Parses an analytics query, such as
+/// `top 10 players by goals+assists in 'Premier League' England 2023/24 where matches >= 10`.
+///
+/// ```text
+/// (top | bottom) <limit> (players | teams)
+///     [by <metric> [asc | desc], ...]
+///     [ties by (<metric> [asc | desc] | name), ...]
+///     [show <metric>, ...]
+///     in <event> <location> <season> [male | female]
+///     [min <matches> matches]
+///     [offset <rows>]
+///     [rank (standard | dense | ordinal)]
+///     [where <predicate>]
+/// ```
+///
+/// `top` sorts the metrics in descending order and `bottom` in ascending order, unless a
+/// sort key or tiebreaker says otherwise. The rows show the sort metrics, unless `show` lists others.
+/// The season is a year such as `2024`, two years such as `2023/24` (from July to June),
+/// or the exact dates such as `2023-08-11..2024-05-19`. Texts are quoted when they have
+/// spaces, and metrics take short names such as `goals`, `matches` or `goals+assists`.
+pub fn parse_query(input: &str) -> Result<Query, ParseError> {
+    let mut parser = Parser::new(input)?;
+    let query = parser.query()?;
+    Ok(query)
+}
+
+/// Short names for metrics, along with their full names.
+const METRIC_ALIASES: &[(&str, MetricKind)] = &[
+    ("goals", MetricKind::GoalsScored),
+    ("matches", MetricKind::MatchesPlayed),
+    ("goals+assists", MetricKind::GoalContributions),
+    ("assists+goals", MetricKind::GoalContributions),
+    ("possession", MetricKind::AverageBallPossession),
+    ("shots", MetricKind::ShotsTotal),
+    ("corners", MetricKind::CornerKicks),
+    ("cards", MetricKind::CardsGiven),
+    ("headers", MetricKind::GoalsByHead),
+    ("penalties", MetricKind::GoalsByPenalty),
+];
+
+pub(crate) fn metric_kind(name: &str) -> Option<MetricKind> {
+    METRIC_ALIASES
+        .iter()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(name))
+        .map(|(_, metric)| metric.clone())
+        .or_else(|| name.parse().ok())
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    tokens: Vec<Spanned>,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Result<Self, ParseError> {
+        Ok(Parser {
+            input,
+            tokens: tokenize(input)?,
+            position: 0,
+        })
+    }
+
+    fn peek(&self) -> &Spanned {
+        &self.tokens[self.position]
+    }
+
+    fn next(&mut self) -> Spanned {
+        let token = self.tokens[self.position].clone();
+        if token.token != Token::End {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn error(&self, message: impl Into<String>, token: &Spanned) -> ParseError {
+        ParseError::new(message, self.input, token.start, token.end)
+    }
+
+    /// Consumes the keyword, ignoring case, if it is the next token.
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match &self.peek().token {
+            Token::Ident(word) if word.eq_ignore_ascii_case(keyword) => {
+                self.next();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Consumes the end of input, or fails with what else was expected.
+    fn expect_end(&mut self, expected: &str) -> Result<(), ParseError> {
+        let token = self.next();
+        match token.token {
+            Token::End => Ok(()),
+            _ => Err(self.error(
+                format!(
+                    "Unexpected {}, expected {} or the end",
+                    token.token, expected
+                ),
+                &token,
+            )),
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), ParseError> {
+        if self.keyword(keyword) {
+            return Ok(());
+        }
+        let token = self.next();
+        Err(self.error(
+            format!("Unexpected {}, expected `{}`", token.token, keyword),
+            &token,
+        ))
+    }
+
+    // ---- queries
+
+    fn query(&mut self) -> Result<Query, ParseError> {
+        let order = if self.keyword("top") {
+            Sort::Desc
+        } else if self.keyword("bottom") {
+            Sort::Asc
+        } else {
+            let token = self.next();
+            return Err(self.error(
+                format!("Unexpected {}, expected `top` or `bottom`", token.token),
+                &token,
+            ));
+        };
+        let limit = self.count("the number of rows", 1)?;
+        let dimension = self.dimension()?;
+
+        let mut sort = vec![];
+        if self.keyword("by") {
+            sort.push(self.sort_key(&order)?);
+            while self.comma() {
+                sort.push(self.sort_key(&order)?);
+            }
+        }
+        let mut tiebreakers = vec![];
+        if self.keyword("ties") {
+            self.expect_keyword("by")?;
+            tiebreakers.push(self.tiebreaker(&order)?);
+            while self.comma() {
+                tiebreakers.push(self.tiebreaker(&order)?);
+            }
+        }
+        let metrics = if self.keyword("show") {
+            let mut metrics = vec![self.metric()?];
+            while self.comma() {
+                metrics.push(self.metric()?);
+            }
+            metrics
+        } else if sort.is_empty() {
+            let token = self.next();
+            return Err(self.error(
+                format!("Unexpected {}, expected `by` or `show`", token.token),
+                &token,
+            ));
+        } else {
+            sort_metrics(&sort)
+        };
+
+        self.expect_keyword("in")?;
+        let event = self.text("the event, such as 'Premier League'")?;
+        let location = self.text("the location, such as England")?;
+        let (season_start, season_end) = self.season()?;
+        let gender = self.gender();
+
+        // The optional clauses, in order, as to tell the ones still expected
+        let clauses = ["`min`", "`offset`", "`rank`", "`where`"];
+        let mut next = 0;
+        let mut min_matches = 0;
+        if self.keyword("min") {
+            min_matches = self.count("the least matches played", 0)?;
+            self.expect_keyword("matches")?;
+            next = 1;
+        }
+        let mut offset = 0;
+        if self.keyword("offset") {
+            offset = self.count("the number of rows skipped", 0)?;
+            next = 2;
+        }
+        let mut ranking = Ranking::default();
+        if self.keyword("rank") {
+            ranking = self.ranking()?;
+            next = 3;
+        }
+        let filter = if self.keyword("where") {
+            let filter = self.predicate()?;
+            self.expect_end("`and`, `or`")?;
+            Some(filter)
+        } else {
+            self.expect_end(&clauses[next..].join(", "))?;
+            None
+        };
+
+        Ok(Query {
+            event,
+            location,
+            season_start,
+            season_end,
+            gender,
+            dimension,
+            metrics,
+            sort,
+            tiebreakers,
+            ranking,
+            offset,
+            limit,
+            min_matches,
+            filter,
+        })
+    }
+
+    fn comma(&mut self) -> bool {
+        if self.peek().token == Token::Comma {
+            self.next();
+            return true;
+        }
+        false
+    }
+
+    /// A whole number, from the given least one.
+    fn count(&mut self, expected: &str, least: u32) -> Result<u32, ParseError> {
+        let token = self.next();
+        match token.token {
+            Token::Number(number)
+                if number.fract() == 0.0 && (least as f64..=u32::MAX as f64).contains(&number) =>
+            {
+                Ok(number as u32)
+            }
+            _ => Err(self.error(
+                format!(
+                    "Unexpected {}, expected {}, a whole number from {}",
+                    token.token, expected, least
+                ),
+                &token,
+            )),
+        }
+    }
+
+    /// A metric, in the given order unless followed by `asc` or `desc`.
+    fn sort_key(&mut self, order: &Sort) -> Result<SortKey, ParseError> {
+        let metric = self.metric()?;
+        let order = if self.keyword("asc") {
+            Sort::Asc
+        } else if self.keyword("desc") {
+            Sort::Desc
+        } else {
+            order.clone()
+        };
+        Ok(SortKey { metric, order })
+    }
+
+    fn tiebreaker(&mut self, order: &Sort) -> Result<Tiebreaker, ParseError> {
+        if self.keyword("name") {
+            return Ok(Tiebreaker::Name);
+        }
+        Ok(Tiebreaker::Metric(self.sort_key(order)?))
+    }
+
+    fn ranking(&mut self) -> Result<Ranking, ParseError> {
+        let token = self.next();
+        match &token.token {
+            Token::Ident(word) => word.parse().ok(),
+            _ => None,
+        }
+        .ok_or_else(|| {
+            self.error(
+                format!(
+                    "Unexpected {}, expected `standard`, `dense` or `ordinal`",
+                    token.token
+                ),
+                &token,
+            )
+        })
+    }
+
+    fn dimension(&mut self) -> Result<Dimension, ParseError> {
+        if self.keyword("players") || self.keyword("player") {
+            return Ok(Dimension::Player);
+        }
+        if self.keyword("teams") || self.keyword("team") {
+            return Ok(Dimension::Team);
+        }
+        let token = self.next();
+        Err(self.error(
+            format!("Unexpected {}, expected `players` or `teams`", token.token),
+            &token,
+        ))
+    }
+
+    /// The gender, male unless said otherwise.
+    fn gender(&mut self) -> Gender {
+        if self.keyword("female") || self.keyword("women") {
+            return Gender::Female;
+        }
+        if !self.keyword("male") {
+            self.keyword("men");
+        }
+        Gender::Male
+    }
+
+    fn metric(&mut self) -> Result<MetricKind, ParseError> {
+        let token = self.next();
+        let Token::Ident(name) = &token.token else {
+            return Err(self.error(
+                format!(
+                    "Unexpected {}, expected a metric such as goals_scored",
+                    token.token
+                ),
+                &token,
+            ));
+        };
+        metric_kind(name).ok_or_else(|| {
+            self.error(
+                format!(
+                    "Unknown metric `{}`, expected a metric such as goals_scored",
+                    name
+                ),
+                &token,
+            )
+        })
+    }
+
+    fn text(&mut self, expected: &str) -> Result<String, ParseError> {
+        let token = self.next();
+        match token.token.clone() {
+            Token::Ident(text) | Token::Str(text) => Ok(text),
+            other => Err(self.error(
+                format!("Unexpected {}, expected {}", other, expected),
+                &token,
+            )),
+        }
+    }
+
+    fn season(&mut self) -> Result<(NaiveDate, NaiveDate), ParseError> {
+        let token = self.next();
+        let season = match &token.token {
+            Token::Number(year) if year.fract() == 0.0 => calendar_year(*year as i32),
+            Token::Ident(season) => match season.split_once("..") {
+                Some((start, end)) => NaiveDate::parse_from_str(start, "%Y-%m-%d")
+                    .ok()
+                    .zip(NaiveDate::parse_from_str(end, "%Y-%m-%d").ok())
+                    .filter(|(start, end)| start <= end),
+                None => season.split_once('/').and_then(|(start, end)| {
+                    let start: i32 = start.parse().ok()?;
+                    let end: i32 = end.parse().ok()?;
+                    let next = start + 1;
+                    (end == next || end == next % 100).then(|| season_years(start))?
+                }),
+            },
+            _ => None,
+        };
+        season.ok_or_else(|| {
+            self.error(
+                format!(
+                    "Unexpected {}, expected the season, such as 2023/24, 2024 or 2023-08-11..2024-05-19",
+                    token.token
+                ),
+                &token,
+            )
+        })
+    }
+
+    fn predicate(&mut self) -> Result<Predicate, ParseError> {
+        let mut predicate = self.and()?;
+        while self.keyword("or") {
+            predicate = Predicate::Or(Box::new(predicate), Box::new(self.and()?));
+        }
+        Ok(predicate)
+    }
+
+    fn and(&mut self) -> Result<Predicate, ParseError> {
+        let mut predicate = self.not()?;
+        while self.keyword("and") {
+            predicate = Predicate::And(Box::new(predicate), Box::new(self.not()?));
+        }
+        Ok(predicate)
+    }
+
+    fn not(&mut self) -> Result<Predicate, ParseError> {
+        if self.keyword("not") {
+            return Ok(Predicate::Not(Box::new(self.not()?)));
+        }
+        if self.peek().token == Token::LParen {
+            self.next();
+            let predicate = self.predicate()?;
+            let token = self.next();
+            return match token.token {
+                Token::RParen => Ok(predicate),
+                _ => Err(self.error(format!("Unexpected {}, expected `)`", token.token), &token)),
+            };
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Predicate, ParseError> {
+        let field = self.next();
+        let Token::Ident(name) = &field.token else {
+            return Err(self.error(
+                format!(
+                    "Unexpected {}, expected a metric, `team` or `player`",
+                    field.token
+                ),
+                &field,
+            ));
+        };
+        let name = name.to_lowercase();
+        let op = self.next();
+        let Token::Op(symbol) = op.token.clone() else {
+            return Err(self.error(
+                format!(
+                    "Unexpected {}, expected a comparison such as `>=`",
+                    op.token
+                ),
+                &op,
+            ));
+        };
+        let value = self.next();
+
+        if name == "team" || name == "player" {
+            let op = match symbol {
+                "=" => TextComparison::Eq,
+                "!=" => TextComparison::Ne,
+                "~" => TextComparison::Contains,
+                _ => {
+                    return Err(self.error(
+                        format!(
+                            "`{}` compares numbers, use `=`, `!=` or `~` for {}",
+                            symbol, name
+                        ),
+                        &op,
+                    ))
+                }
+            };
+            let text = match value.token.clone() {
+                Token::Ident(text) | Token::Str(text) => text,
+                other => {
+                    return Err(self.error(format!("Unexpected {}, expected a text", other), &value))
+                }
+            };
+            return Ok(if name == "team" {
+                Predicate::Team { op, value: text }
+            } else {
+                Predicate::Player { op, value: text }
+            });
+        }
+
+        let metric = metric_kind(&name).ok_or_else(|| {
+            self.error(
+                format!("Unknown metric `{}`, expected a metric such as goals_scored, `team` or `player`", name),
+                &field,
+            )
+        })?;
+        let op = match symbol {
+            "=" => Comparison::Eq,
+            "!=" => Comparison::Ne,
+            "<" => Comparison::Lt,
+            "<=" => Comparison::Le,
+            ">" => Comparison::Gt,
+            ">=" => Comparison::Ge,
+            _ => {
+                return Err(self.error(
+                    format!(
+                        "`{}` compares texts, use a comparison such as `>=` for {}",
+                        symbol, name
+                    ),
+                    &op,
+                ))
+            }
+        };
+        let Token::Number(number) = value.token else {
+            return Err(self.error(
+                format!("Unexpected {}, expected a number", value.token),
+                &value,
+            ));
+        };
+        Ok(Predicate::Metric {
+            metric,
+            op,
+            value: number,
+        })
+    }
+}
+
+/// The metrics of the sort keys, each once, in order.
+pub(crate) fn sort_metrics(sort: &[SortKey]) -> Vec<MetricKind> {
+    let mut metrics: Vec<MetricKind> = vec![];
+    for key in sort {
+        if !metrics.contains(&key.metric) {
+            metrics.push(key.metric.clone());
+        }
+    }
+    metrics
+}
+
+/// A season played within a year, from January to December.
+pub(crate) fn calendar_year(year: i32) -> Option<(NaiveDate, NaiveDate)> {
+    Some((
+        NaiveDate::from_ymd_opt(year, 1, 1)?,
+        NaiveDate::from_ymd_opt(year, 12, 31)?,
+    ))
+}
+
+/// A season played over two years, from July to June.
+pub(crate) fn season_years(start: i32) -> Option<(NaiveDate, NaiveDate)> {
+    Some((
+        NaiveDate::from_ymd_opt(start, 7, 1)?,
+        NaiveDate::from_ymd_opt(start + 1, 6, 30)?,
+    ))
+}
@@ -1,28 +1,30 @@
 use super::in_memo::InMemoRepository;
 use super::model::Competition;
+use crate::id::CompetitionId;
 use serde_derive::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::Arc;
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CompetitionsRepo {
-    data: HashMap<Arc<String>, Competition>,
+    data: HashMap<CompetitionId, Competition>,
 }
 
 impl InMemoRepository<Competition> for CompetitionsRepo {
+    type Key = CompetitionId;
+
     fn new() -> Self {
         Self::default()
     }
 
     fn push(&mut self, value: Competition) {
-        self.data.insert(Arc::clone(&value.id), value);
+        self.data.insert(value.id.clone(), value);
     }
 
-    fn insert(&mut self, key: Arc<String>, value: Competition) {
+    fn insert(&mut self, key: CompetitionId, value: Competition) {
         self.data.insert(key, value);
     }
 
-    fn all(&self) -> &HashMap<Arc<String>, Competition> {
+    fn all(&self) -> &HashMap<CompetitionId, Competition> {
         &self.data
     }
 }
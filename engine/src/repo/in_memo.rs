@@ -1,25 +1,27 @@
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::hash::Hash;
+
+pub trait InMemoRepository<V> {
+    type Key: Clone + Eq + Hash;
 
-pub trait InMemoRepository<V: Default> {
     fn new() -> Self
     where
         Self: Default;
 
     fn push(&mut self, value: V);
-    fn insert(&mut self, key: Arc<String>, value: V);
-    fn all(&self) -> &HashMap<Arc<String>, V>;
+    fn insert(&mut self, key: Self::Key, value: V);
+    fn all(&self) -> &HashMap<Self::Key, V>;
 
     /// Returns an iterator that filters values based on a predicate function.
     /// The iterator produces a set of references to the values that satisfy the predicate.
-    fn filter_iter<'a, F>(&'a self, predicate: F) -> impl Iterator<Item = (Arc<String>, &V)>
+    fn filter_iter<'a, F>(&'a self, predicate: F) -> impl Iterator<Item = (Self::Key, &'a V)>
     where
         F: Fn(&V) -> bool + 'a,
         V: 'a,
     {
         self.all().iter().filter_map(move |(key, value)| {
             if predicate(value) {
-                Some((Arc::clone(key), value))
+                Some((key.clone(), value))
             } else {
                 None
             }
@@ -27,13 +29,13 @@ pub trait InMemoRepository<V: Default> {
     }
 
     /// Finds a value by its key.
-    fn find(&self, key: &Arc<String>) -> Option<&V> {
+    fn find(&self, key: &Self::Key) -> Option<&V> {
         self.all().get(key)
     }
 
     /// Returns an iterator that maps values based on a mapping function.
     /// The iterator produces a new HashMap where each value is transformed by the mapping function.
-    fn map_iter<'a, F, W>(&'a self, mapper: F) -> impl Iterator<Item = (Arc<String>, W)>
+    fn map_iter<'a, F, W>(&'a self, mapper: F) -> impl Iterator<Item = (Self::Key, W)>
     where
         F: Fn(&V) -> W + 'a,
         V: 'a,
@@ -41,6 +43,6 @@ pub trait InMemoRepository<V: Default> {
     {
         self.all()
             .iter()
-            .map(move |(key, value)| (Arc::clone(key), mapper(value)))
+            .map(move |(key, value)| (key.clone(), mapper(value)))
     }
 }
@@ -1,6 +1,7 @@
 use chrono::NaiveDate;
 use serde_derive::{Deserialize, Serialize};
-use std::sync::Arc;
+
+use crate::id::{CompetitionId, CompetitorId, PlayerId};
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -10,9 +11,9 @@ pub enum Gender {
     Female,
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Competition {
-    pub id: Arc<String>,
+    pub id: CompetitionId,
     pub name: String,
     pub location: String,
     pub gender: Gender,
@@ -20,54 +21,144 @@ pub struct Competition {
     pub season_end: NaiveDate,
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Team {
-    pub id: Arc<String>,
+    pub id: CompetitorId,
     pub name: String,
     pub abbreviation: String,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, PartialOrd, Eq, Ord)]
-#[serde(rename_all = "lowercase")]
-pub enum Metric {
-    #[serde(rename = "goals_scored")]
-    GoalsScored {
-        value: u32,
-    },
-    Assists {
-        value: u32,
-    },
+/// Declares a metrics enum, each variant holding its value, along with the metric names
+/// as serialized and stored, such as "goals_scored".
+macro_rules! metrics {
+    (
+        $(#[$meta:meta])*
+        $name:ident($value:ty) {
+            $($variant:ident => $metric:literal),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        pub enum $name {
+            $(
+                #[serde(rename = $metric)]
+                $variant { value: $value },
+            )+
+        }
+
+        impl $name {
+            pub fn value(&self) -> $value {
+                match self {
+                    $($name::$variant { value } => *value,)+
+                }
+            }
+
+            /// The metric name, as serialized, such as "goals_scored".
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $($name::$variant { .. } => $metric,)+
+                }
+            }
+
+            /// The metric of the given name, if known.
+            pub fn from_name(name: &str, value: $value) -> Option<Self> {
+                match name {
+                    $($metric => Some($name::$variant { value }),)+
+                    _ => None,
+                }
+            }
+        }
+    };
 }
 
+metrics!(
+    /// A statistic of a player over a competition season. The statistics the provider
+    /// does not report for a player are left out, rather than zero.
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, PartialOrd, Eq, Ord)]
+    Metric(u32) {
+        GoalsScored => "goals_scored",
+        Assists => "assists",
+        CardsGiven => "cards_given",
+        GoalsByHead => "goals_by_head",
+        GoalsByPenalty => "goals_by_penalty",
+        GoalsConceded => "goals_conceded",
+        MatchesPlayed => "matches_played",
+        Offsides => "offsides",
+        OwnGoals => "own_goals",
+        PenaltiesMissed => "penalties_missed",
+        RedCards => "red_cards",
+        ShotsOffTarget => "shots_off_target",
+        SubstitutedIn => "substituted_in",
+        SubstitutedOut => "substituted_out",
+        YellowCards => "yellow_cards",
+        YellowRedCards => "yellow_red_cards",
+    }
+);
+
 impl Default for Metric {
     fn default() -> Self {
         Metric::GoalsScored { value: 0 }
     }
 }
 
-impl Metric {
-    pub fn value(&self) -> u32 {
-        match self {
-            Metric::GoalsScored { value } => *value,
-            Metric::Assists { value } => *value,
-        }
+metrics!(
+    /// A statistic of a team over a competition season, as reported by the provider
+    /// rather than summed over its players.
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, PartialOrd)]
+    TeamMetric(f64) {
+        AverageBallPossession => "average_ball_possession",
+        CardsGiven => "cards_given",
+        CornerKicks => "corner_kicks",
+        FreeKicks => "free_kicks",
+        GoalsByFoot => "goals_by_foot",
+        GoalsByHead => "goals_by_head",
+        GoalsConceded => "goals_conceded",
+        GoalsConcededFirstHalf => "goals_conceded_first_half",
+        GoalsConcededSecondHalf => "goals_conceded_second_half",
+        GoalsScored => "goals_scored",
+        GoalsScoredFirstHalf => "goals_scored_first_half",
+        GoalsScoredSecondHalf => "goals_scored_second_half",
+        MatchesPlayed => "matches_played",
+        Offsides => "offsides",
+        PenaltiesMissed => "penalties_missed",
+        RedCards => "red_cards",
+        ShotsBlocked => "shots_blocked",
+        ShotsOffTarget => "shots_off_target",
+        ShotsOnBar => "shots_on_bar",
+        ShotsOnPost => "shots_on_post",
+        ShotsOnTarget => "shots_on_target",
+        ShotsTotal => "shots_total",
+        YellowCards => "yellow_cards",
+        YellowRedCards => "yellow_red_cards",
+    }
+);
+
+impl Default for TeamMetric {
+    fn default() -> Self {
+        TeamMetric::GoalsScored { value: 0.0 }
     }
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Player {
-    pub id: Arc<String>,
+    pub id: PlayerId,
     pub name: String,
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PlayerStats {
-    pub player_id: Arc<String>,
-    pub team_id: Arc<String>,
-    pub competition_id: Arc<String>,
+    pub player_id: PlayerId,
+    pub team_id: CompetitorId,
+    pub competition_id: CompetitionId,
     pub metrics: Vec<Metric>,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TeamStats {
+    pub team_id: CompetitorId,
+    pub competition_id: CompetitionId,
+    pub metrics: Vec<TeamMetric>,
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct PlayerDetails<'a> {
     pub player_id: &'a str,
@@ -79,3 +170,19 @@ pub struct PlayerDetails<'a> {
 }
 unsafe impl<'a> Send for PlayerDetails<'a> {}
 unsafe impl<'a> Sync for PlayerDetails<'a> {}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TeamDetails<'a> {
+    pub team_id: &'a str,
+    pub team_name: &'a str,
+    pub team_abbreviation: &'a str,
+    pub competition_id: &'a str,
+    pub competition_name: &'a str,
+}
+
+/// A group of player rows, such as a team or a competition.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GroupDetails<'a> {
+    pub group_id: &'a str,
+    pub group_name: &'a str,
+}
@@ -10,16 +10,17 @@ pub struct PlayerStatsRepo {
 }
 
 impl InMemoRepository<PlayerStats> for PlayerStatsRepo {
+    type Key = Arc<String>;
+
     fn new() -> Self {
         Self::default()
     }
 
     fn push(&mut self, value: PlayerStats) {
-        let player_id = Arc::clone(&value.player_id).to_string();
-        let team_id = Arc::clone(&value.team_id).to_string();
-        let competition_id = Arc::clone(&value.competition_id).to_string();
-
-        let key = format!("{}::{}::{}", player_id, team_id, competition_id);
+        let key = format!(
+            "{}::{}::{}",
+            value.player_id, value.team_id, value.competition_id
+        );
         self.data.insert(Arc::new(key), value);
     }
 
@@ -1,28 +1,30 @@
 use super::in_memo::InMemoRepository;
 use super::model::Player;
+use crate::id::PlayerId;
 use serde_derive::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::Arc;
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PlayersRepo {
-    data: HashMap<Arc<String>, Player>,
+    data: HashMap<PlayerId, Player>,
 }
 
 impl InMemoRepository<Player> for PlayersRepo {
+    type Key = PlayerId;
+
     fn new() -> Self {
         Self::default()
     }
 
     fn push(&mut self, value: Player) {
-        self.data.insert(Arc::clone(&value.id), value);
+        self.data.insert(value.id.clone(), value);
     }
 
-    fn insert(&mut self, key: Arc<String>, value: Player) {
+    fn insert(&mut self, key: PlayerId, value: Player) {
         self.data.insert(key, value);
     }
 
-    fn all(&self) -> &HashMap<Arc<String>, Player> {
+    fn all(&self) -> &HashMap<PlayerId, Player> {
         &self.data
     }
 }
@@ -0,0 +1,483 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use super::model::{Competition, Gender, Metric, Player, PlayerStats, Team, TeamMetric, TeamStats};
+use super::storage::Storage;
+use crate::id::{CompetitorId, PlayerId};
+
+// --------------------------------------------------
+// Schema migrations
+// --------------------------------------------------
+
+/// Schema migrations, applied in order. The schema version is the number of migrations
+/// applied, kept in `PRAGMA user_version`. Never edit a released migration, append a new one.
+const MIGRATIONS: &[&str] = &[
+    // 1: competitions, teams, players, player_stats and metrics
+    "CREATE TABLE competitions (
+        id TEXT NOT NULL,
+        season_start TEXT NOT NULL,
+        season_end TEXT NOT NULL,
+        name TEXT NOT NULL,
+        location TEXT NOT NULL,
+        gender TEXT NOT NULL,
+        PRIMARY KEY (id, season_start, season_end)
+    );
+    CREATE TABLE teams (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        abbreviation TEXT NOT NULL
+    );
+    CREATE TABLE players (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL
+    );
+    CREATE TABLE player_stats (
+        id INTEGER PRIMARY KEY,
+        player_id TEXT NOT NULL REFERENCES players (id),
+        team_id TEXT NOT NULL REFERENCES teams (id),
+        competition_id TEXT NOT NULL,
+        season_start TEXT NOT NULL,
+        season_end TEXT NOT NULL,
+        UNIQUE (player_id, team_id, competition_id, season_start, season_end),
+        FOREIGN KEY (competition_id, season_start, season_end)
+            REFERENCES competitions (id, season_start, season_end)
+    );
+    CREATE TABLE metrics (
+        player_stats_id INTEGER NOT NULL REFERENCES player_stats (id) ON DELETE CASCADE,
+        name TEXT NOT NULL,
+        value INTEGER NOT NULL,
+        PRIMARY KEY (player_stats_id, name)
+    );",
+    // 2: player stats looked up per competition season
+    "CREATE INDEX player_stats_by_season
+        ON player_stats (competition_id, season_start, season_end);",
+    // 3: when the data of a competition season was fetched from the provider
+    "ALTER TABLE competitions ADD COLUMN synced_at TEXT;",
+    // 4: team_stats and team_metrics, as reported by the provider per team
+    "CREATE TABLE team_stats (
+        id INTEGER PRIMARY KEY,
+        team_id TEXT NOT NULL REFERENCES teams (id),
+        competition_id TEXT NOT NULL,
+        season_start TEXT NOT NULL,
+        season_end TEXT NOT NULL,
+        UNIQUE (team_id, competition_id, season_start, season_end),
+        FOREIGN KEY (competition_id, season_start, season_end)
+            REFERENCES competitions (id, season_start, season_end)
+    );
+    CREATE TABLE team_metrics (
+        team_stats_id INTEGER NOT NULL REFERENCES team_stats (id) ON DELETE CASCADE,
+        name TEXT NOT NULL,
+        value REAL NOT NULL,
+        PRIMARY KEY (team_stats_id, name)
+    );",
+];
+
+// --------------------------------------------------
+// SQLite storage
+// --------------------------------------------------
+
+/// Stores the repositories data in a SQLite database, which standard tools can inspect.
+#[derive(Debug)]
+pub struct SqliteStorage {
+    conn: Connection,
+}
+
+impl SqliteStorage {
+    /// Opens (or creates) the database at the given path and migrates it to the latest schema.
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let conn = Connection::open(path)
+            .map_err(|e| anyhow!("Failed to open database {}: {}", path.display(), e))?;
+        Self::with_connection(conn)
+    }
+
+    /// A database living in memory only, as for tests.
+    pub fn in_memory() -> Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Self> {
+        conn.pragma_update(None, "foreign_keys", true)?;
+        let storage = SqliteStorage { conn };
+        storage.migrate()?;
+        Ok(storage)
+    }
+
+    /// The schema version of the database.
+    pub fn schema_version(&self) -> Result<usize> {
+        let version: i64 = self
+            .conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))?;
+        Ok(version as usize)
+    }
+
+    /// Applies the migrations the database is missing, each in its own transaction.
+    fn migrate(&self) -> Result<()> {
+        let version = self.schema_version()?;
+        if version > MIGRATIONS.len() {
+            return Err(anyhow!(
+                "Database schema version {} is newer than the supported {}, upgrade sport-cli",
+                version,
+                MIGRATIONS.len()
+            ));
+        }
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            self.conn
+                .execute_batch(&format!(
+                    "BEGIN; {} PRAGMA user_version = {}; COMMIT;",
+                    migration,
+                    index + 1
+                ))
+                .map_err(|e| {
+                    let _ = self.conn.execute_batch("ROLLBACK;");
+                    anyhow!("Failed to apply migration {}: {}", index + 1, e)
+                })?;
+        }
+        Ok(())
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn transaction<F>(&self, writes: F) -> Result<()>
+    where
+        F: FnOnce(&Self) -> Result<()>,
+    {
+        self.conn.execute_batch("BEGIN;")?;
+        match writes(self) {
+            Ok(()) => Ok(self.conn.execute_batch("COMMIT;")?),
+            Err(e) => {
+                self.conn.execute_batch("ROLLBACK;")?;
+                Err(e)
+            }
+        }
+    }
+
+    fn save_competition(&self, competition: &Competition) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO competitions (id, season_start, season_end, name, location, gender)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT (id, season_start, season_end)
+             DO UPDATE SET name = ?4, location = ?5, gender = ?6",
+            params![
+                competition.id.as_str(),
+                competition.season_start,
+                competition.season_end,
+                competition.name,
+                competition.location,
+                gender_name(&competition.gender),
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn save_team(&self, team: &Team) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO teams (id, name, abbreviation) VALUES (?1, ?2, ?3)
+             ON CONFLICT (id) DO UPDATE SET name = ?2, abbreviation = ?3",
+            params![team.id.as_str(), team.name, team.abbreviation],
+        )?;
+        Ok(())
+    }
+
+    fn save_player(&self, player: &Player) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO players (id, name) VALUES (?1, ?2)
+             ON CONFLICT (id) DO UPDATE SET name = ?2",
+            params![player.id.as_str(), player.name],
+        )?;
+        Ok(())
+    }
+
+    fn save_player_stats(&self, season: &Competition, player_stats: &PlayerStats) -> Result<()> {
+        let player_stats_id: i64 = self.conn.query_row(
+            "INSERT INTO player_stats
+                (player_id, team_id, competition_id, season_start, season_end)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT (player_id, team_id, competition_id, season_start, season_end)
+             DO UPDATE SET player_id = player_id
+             RETURNING id",
+            params![
+                player_stats.player_id.as_str(),
+                player_stats.team_id.as_str(),
+                player_stats.competition_id.as_str(),
+                season.season_start,
+                season.season_end,
+            ],
+            |row| row.get(0),
+        )?;
+
+        // The latest metrics replace the previous ones
+        self.conn.execute(
+            "DELETE FROM metrics WHERE player_stats_id = ?1",
+            params![player_stats_id],
+        )?;
+        for metric in &player_stats.metrics {
+            self.conn.execute(
+                "INSERT INTO metrics (player_stats_id, name, value) VALUES (?1, ?2, ?3)",
+                params![player_stats_id, metric.name(), metric.value()],
+            )?;
+        }
+        Ok(())
+    }
+
+    fn save_team_stats(&self, season: &Competition, team_stats: &TeamStats) -> Result<()> {
+        let team_stats_id: i64 = self.conn.query_row(
+            "INSERT INTO team_stats (team_id, competition_id, season_start, season_end)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (team_id, competition_id, season_start, season_end)
+             DO UPDATE SET team_id = team_id
+             RETURNING id",
+            params![
+                team_stats.team_id.as_str(),
+                team_stats.competition_id.as_str(),
+                season.season_start,
+                season.season_end,
+            ],
+            |row| row.get(0),
+        )?;
+
+        // The latest metrics replace the previous ones
+        self.conn.execute(
+            "DELETE FROM team_metrics WHERE team_stats_id = ?1",
+            params![team_stats_id],
+        )?;
+        for metric in &team_stats.metrics {
+            self.conn.execute(
+                "INSERT INTO team_metrics (team_stats_id, name, value) VALUES (?1, ?2, ?3)",
+                params![team_stats_id, metric.name(), metric.value()],
+            )?;
+        }
+        Ok(())
+    }
+
+    fn clear_season_stats(&self, season: &Competition) -> Result<()> {
+        // Their metrics are deleted in cascade
+        for table in ["player_stats", "team_stats"] {
+            self.conn.execute(
+                &format!(
+                    "DELETE FROM {}
+                     WHERE competition_id = ?1 AND season_start = ?2 AND season_end = ?3",
+                    table
+                ),
+                params![season.id.as_str(), season.season_start, season.season_end],
+            )?;
+        }
+        Ok(())
+    }
+
+    fn mark_synced(&self, season: &Competition, synced_at: DateTime<Utc>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE competitions SET synced_at = ?4
+             WHERE id = ?1 AND season_start = ?2 AND season_end = ?3",
+            params![
+                season.id.as_str(),
+                season.season_start,
+                season.season_end,
+                synced_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn synced_at(&self, season: &Competition) -> Result<Option<DateTime<Utc>>> {
+        let synced_at = self
+            .conn
+            .query_row(
+                "SELECT synced_at FROM competitions
+                 WHERE id = ?1 AND season_start = ?2 AND season_end = ?3",
+                params![season.id.as_str(), season.season_start, season.season_end],
+                |row| row.get::<_, Option<DateTime<Utc>>>(0),
+            )
+            .optional()?;
+        Ok(synced_at.flatten())
+    }
+
+    fn competitions(&self) -> Result<Vec<Competition>> {
+        let mut statement = self.conn.prepare(
+            "SELECT id, season_start, season_end, name, location, gender
+             FROM competitions ORDER BY id, season_start",
+        )?;
+        let rows = statement
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, NaiveDate>(1)?,
+                    row.get::<_, NaiveDate>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                    row.get::<_, String>(5)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        rows.into_iter()
+            .map(|(id, season_start, season_end, name, location, gender)| {
+                Ok(Competition {
+                    id: parse_id(&id)?,
+                    name,
+                    location,
+                    gender: match gender.as_str() {
+                        "male" => Gender::Male,
+                        "female" => Gender::Female,
+                        _ => return Err(anyhow!("Invalid gender in database: {}", gender)),
+                    },
+                    season_start,
+                    season_end,
+                })
+            })
+            .collect()
+    }
+
+    fn find_team(&self, id: &CompetitorId) -> Result<Option<Team>> {
+        let team = self
+            .conn
+            .query_row(
+                "SELECT id, name, abbreviation FROM teams WHERE id = ?1",
+                params![id.as_str()],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                    ))
+                },
+            )
+            .optional()?;
+        team.map(|(id, name, abbreviation)| {
+            Ok(Team {
+                id: parse_id(&id)?,
+                name,
+                abbreviation,
+            })
+        })
+        .transpose()
+    }
+
+    fn find_player(&self, id: &PlayerId) -> Result<Option<Player>> {
+        let player = self
+            .conn
+            .query_row(
+                "SELECT id, name FROM players WHERE id = ?1",
+                params![id.as_str()],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+            )
+            .optional()?;
+        player
+            .map(|(id, name)| {
+                Ok(Player {
+                    id: parse_id(&id)?,
+                    name,
+                })
+            })
+            .transpose()
+    }
+
+    fn player_stats(&self, season: &Competition) -> Result<Vec<PlayerStats>> {
+        let mut statement = self.conn.prepare(
+            "SELECT s.id, s.player_id, s.team_id, m.name, m.value
+             FROM player_stats s LEFT JOIN metrics m ON m.player_stats_id = s.id
+             WHERE s.competition_id = ?1 AND s.season_start = ?2 AND s.season_end = ?3
+             ORDER BY s.id, m.rowid",
+        )?;
+        let rows = statement
+            .query_map(
+                params![season.id.as_str(), season.season_start, season.season_end],
+                |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, Option<String>>(3)?,
+                        row.get::<_, Option<u32>>(4)?,
+                    ))
+                },
+            )?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        // One row per metric, grouped back into their player stats
+        let mut all_stats: Vec<(i64, PlayerStats)> = vec![];
+        for (id, player_id, team_id, name, value) in rows {
+            if all_stats.last().is_none_or(|(last_id, _)| *last_id != id) {
+                all_stats.push((
+                    id,
+                    PlayerStats {
+                        player_id: parse_id(&player_id)?,
+                        team_id: parse_id(&team_id)?,
+                        competition_id: season.id.clone(),
+                        metrics: vec![],
+                    },
+                ));
+            }
+            if let (Some(name), Some(value), Some((_, stats))) = (name, value, all_stats.last_mut())
+            {
+                // Metrics this version does not know about are skipped
+                if let Some(metric) = Metric::from_name(&name, value) {
+                    stats.metrics.push(metric);
+                }
+            }
+        }
+        Ok(all_stats.into_iter().map(|(_, stats)| stats).collect())
+    }
+
+    fn team_stats(&self, season: &Competition) -> Result<Vec<TeamStats>> {
+        let mut statement = self.conn.prepare(
+            "SELECT s.id, s.team_id, m.name, m.value
+             FROM team_stats s LEFT JOIN team_metrics m ON m.team_stats_id = s.id
+             WHERE s.competition_id = ?1 AND s.season_start = ?2 AND s.season_end = ?3
+             ORDER BY s.id, m.rowid",
+        )?;
+        let rows = statement
+            .query_map(
+                params![season.id.as_str(), season.season_start, season.season_end],
+                |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, Option<String>>(2)?,
+                        row.get::<_, Option<f64>>(3)?,
+                    ))
+                },
+            )?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        // One row per metric, grouped back into their team stats
+        let mut all_stats: Vec<(i64, TeamStats)> = vec![];
+        for (id, team_id, name, value) in rows {
+            if all_stats.last().is_none_or(|(last_id, _)| *last_id != id) {
+                all_stats.push((
+                    id,
+                    TeamStats {
+                        team_id: parse_id(&team_id)?,
+                        competition_id: season.id.clone(),
+                        metrics: vec![],
+                    },
+                ));
+            }
+            if let (Some(name), Some(value), Some((_, stats))) = (name, value, all_stats.last_mut())
+            {
+                // Metrics this version does not know about are skipped
+                if let Some(metric) = TeamMetric::from_name(&name, value) {
+                    stats.metrics.push(metric);
+                }
+            }
+        }
+        Ok(all_stats.into_iter().map(|(_, stats)| stats).collect())
+    }
+}
+
+fn gender_name(gender: &Gender) -> &'static str {
+    match gender {
+        Gender::Male => "male",
+        Gender::Female => "female",
+    }
+}
+
+fn parse_id<T: std::str::FromStr<Err = String>>(id: &str) -> Result<T> {
+    id.parse()
+        .map_err(|e| anyhow!("Invalid id in database: {}", e))
+}
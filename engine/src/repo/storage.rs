@@ -0,0 +1,67 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+use super::in_memo::InMemoRepository;
+use super::model::{Competition, Player, PlayerStats, Team, TeamStats};
+use crate::id::{CompetitorId, PlayerId};
+
+/// A persistent store for the repositories data, such as SQLite.
+///
+/// A competition is stored per season, as the provider reuses its id across seasons,
+/// so player stats are saved and read along with the competition season they belong to.
+pub trait Storage {
+    /// Runs the given writes all at once, or none of them if one fails.
+    fn transaction<F>(&self, writes: F) -> Result<()>
+    where
+        F: FnOnce(&Self) -> Result<()>;
+
+    fn save_competition(&self, competition: &Competition) -> Result<()>;
+    fn save_team(&self, team: &Team) -> Result<()>;
+    fn save_player(&self, player: &Player) -> Result<()>;
+    fn save_player_stats(&self, season: &Competition, player_stats: &PlayerStats) -> Result<()>;
+    fn save_team_stats(&self, season: &Competition, team_stats: &TeamStats) -> Result<()>;
+    /// Removes the player and team stats of a competition season, as to save it anew.
+    fn clear_season_stats(&self, season: &Competition) -> Result<()>;
+    /// Records when the data of a competition season was fetched from the provider.
+    fn mark_synced(&self, season: &Competition, synced_at: DateTime<Utc>) -> Result<()>;
+    fn synced_at(&self, season: &Competition) -> Result<Option<DateTime<Utc>>>;
+
+    /// Every competition season stored.
+    fn competitions(&self) -> Result<Vec<Competition>>;
+    fn find_team(&self, id: &CompetitorId) -> Result<Option<Team>>;
+    fn find_player(&self, id: &PlayerId) -> Result<Option<Player>>;
+    /// The player stats of a competition season.
+    fn player_stats(&self, season: &Competition) -> Result<Vec<PlayerStats>>;
+    /// The team stats of a competition season.
+    fn team_stats(&self, season: &Competition) -> Result<Vec<TeamStats>>;
+
+    /// Loads a competition season, with its teams, players and their stats,
+    /// into the in-memory repositories the engine runs against.
+    fn load_season(
+        &self,
+        season: &Competition,
+        competitions: &mut impl InMemoRepository<Competition>,
+        teams: &mut impl InMemoRepository<Team>,
+        players: &mut impl InMemoRepository<Player>,
+        players_stats: &mut impl InMemoRepository<PlayerStats>,
+        teams_stats: &mut impl InMemoRepository<TeamStats>,
+    ) -> Result<()> {
+        competitions.push(season.clone());
+        for player_stats in self.player_stats(season)? {
+            if let Some(team) = self.find_team(&player_stats.team_id)? {
+                teams.push(team);
+            }
+            if let Some(player) = self.find_player(&player_stats.player_id)? {
+                players.push(player);
+            }
+            players_stats.push(player_stats);
+        }
+        for team_stats in self.team_stats(season)? {
+            if let Some(team) = self.find_team(&team_stats.team_id)? {
+                teams.push(team);
+            }
+            teams_stats.push(team_stats);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+use super::in_memo::InMemoRepository;
+use super::model::TeamStats;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TeamStatsRepo {
+    data: HashMap<Arc<String>, TeamStats>,
+}
+
+impl InMemoRepository<TeamStats> for TeamStatsRepo {
+    type Key = Arc<String>;
+
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn push(&mut self, value: TeamStats) {
+        let key = format!("{}::{}", value.team_id, value.competition_id);
+        self.data.insert(Arc::new(key), value);
+    }
+
+    fn insert(&mut self, key: Arc<String>, value: TeamStats) {
+        self.data.insert(key, value);
+    }
+
+    fn all(&self) -> &HashMap<Arc<String>, TeamStats> {
+        &self.data
+    }
+}
@@ -1,28 +1,30 @@
 use super::in_memo::InMemoRepository;
 use super::model::Team;
+use crate::id::CompetitorId;
 use serde_derive::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::Arc;
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TeamsRepo {
-    data: HashMap<Arc<String>, Team>,
+    data: HashMap<CompetitorId, Team>,
 }
 
 impl InMemoRepository<Team> for TeamsRepo {
+    type Key = CompetitorId;
+
     fn new() -> Self {
         Self::default()
     }
 
     fn push(&mut self, value: Team) {
-        self.data.insert(Arc::clone(&value.id), value);
+        self.data.insert(value.id.clone(), value);
     }
 
-    fn insert(&mut self, key: Arc<String>, value: Team) {
+    fn insert(&mut self, key: CompetitorId, value: Team) {
         self.data.insert(key, value);
     }
 
-    fn all(&self) -> &HashMap<Arc<String>, Team> {
+    fn all(&self) -> &HashMap<CompetitorId, Team> {
         &self.data
     }
 }
@@ -1,6 +1,13 @@
 use anyhow::Result;
 use chrono::NaiveDate;
-use engine::{engine::*, repo::model::Gender};
+use engine::{
+    aggregate::*,
+    engine::*,
+    repo::{
+        in_memo::InMemoRepository,
+        model::{Gender, Metric, Player, PlayerStats, TeamMetric, TeamStats},
+    },
+};
 use fixture::Fixture;
 
 mod fixture;
@@ -16,9 +23,17 @@ fn top_2_score_players() -> Result<()> {
         season_end: NaiveDate::from_ymd_opt(2024, 5, 19).unwrap(),
         gender: Gender::Male,
         dimension: Dimension::Player,
-        metric: MetricKind::GoalsScored,
-        sort: Sort::Desc,
+        metrics: vec![MetricKind::GoalsScored],
+        sort: vec![SortKey {
+            metric: MetricKind::GoalsScored,
+            order: Sort::Desc,
+        }],
+        tiebreakers: vec![],
+        ranking: Ranking::Standard,
+        offset: 0,
         limit: 2,
+        min_matches: 0,
+        filter: None,
     };
 
     let result = engine.execute(
@@ -58,9 +73,17 @@ fn top_2_assist_players() -> Result<()> {
         season_end: NaiveDate::from_ymd_opt(2024, 5, 19).unwrap(),
         gender: Gender::Male,
         dimension: Dimension::Player,
-        metric: MetricKind::Assists,
-        sort: Sort::Desc,
+        metrics: vec![MetricKind::Assists],
+        sort: vec![SortKey {
+            metric: MetricKind::Assists,
+            order: Sort::Desc,
+        }],
+        tiebreakers: vec![],
+        ranking: Ranking::Standard,
+        offset: 0,
         limit: 2,
+        min_matches: 0,
+        filter: None,
     };
 
     let result = engine.execute(
@@ -88,3 +111,885 @@ fn top_2_assist_players() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn top_2_score_teams() -> Result<()> {
+    let fixture = Fixture::stub();
+    let engine = Engine;
+    let query = Query {
+        event: "Premier League".to_string(),
+        location: "England".to_string(),
+        season_start: NaiveDate::from_ymd_opt(2023, 8, 11).unwrap(),
+        season_end: NaiveDate::from_ymd_opt(2024, 5, 19).unwrap(),
+        gender: Gender::Male,
+        dimension: Dimension::Team,
+        metrics: vec![MetricKind::GoalsScored],
+        sort: vec![SortKey {
+            metric: MetricKind::GoalsScored,
+            order: Sort::Desc,
+        }],
+        tiebreakers: vec![],
+        ranking: Ranking::Standard,
+        offset: 0,
+        limit: 2,
+        min_matches: 0,
+        filter: None,
+    };
+
+    let result = engine.execute_teams(
+        &fixture.teams_repo,
+        &fixture.team_stats_repo,
+        &fixture.player_stats_repo,
+        &fixture.competitions_repo,
+        &query,
+    );
+
+    let result_teams: Vec<(String, f64)> = result
+        .into_iter()
+        .map(|t| (t.dimension.team_abbreviation.to_string(), t.values[0].value))
+        .collect();
+
+    let expected_teams = vec![("MCI".to_string(), 17.0), ("TOT".to_string(), 4.0)];
+
+    assert_eq!(
+        result_teams, expected_teams,
+        "The result did not match the expected output"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn top_assist_team() -> Result<()> {
+    let fixture = Fixture::stub();
+    let engine = Engine;
+    let query = Query {
+        event: "World Cup".to_string(),
+        location: "International".to_string(),
+        season_start: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+        season_end: NaiveDate::from_ymd_opt(2024, 12, 31).unwrap(),
+        gender: Gender::Male,
+        dimension: Dimension::Team,
+        metrics: vec![MetricKind::Assists],
+        sort: vec![SortKey {
+            metric: MetricKind::Assists,
+            order: Sort::Desc,
+        }],
+        tiebreakers: vec![],
+        ranking: Ranking::Standard,
+        offset: 0,
+        limit: 1,
+        min_matches: 0,
+        filter: None,
+    };
+
+    let result = engine.execute_teams(
+        &fixture.teams_repo,
+        &fixture.team_stats_repo,
+        &fixture.player_stats_repo,
+        &fixture.competitions_repo,
+        &query,
+    );
+
+    let result_teams: Vec<(String, f64)> = result
+        .into_iter()
+        .map(|t| (t.dimension.team_id.to_string(), t.values[0].value))
+        .collect();
+
+    let expected_teams = vec![("sr:competitor:33".to_string(), 8.0)];
+
+    assert_eq!(
+        result_teams, expected_teams,
+        "The result did not match the expected output"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn top_possession_teams() -> Result<()> {
+    let fixture = Fixture::stub();
+    let engine = Engine;
+    let query = Query {
+        event: "Premier League".to_string(),
+        location: "England".to_string(),
+        season_start: NaiveDate::from_ymd_opt(2023, 8, 11).unwrap(),
+        season_end: NaiveDate::from_ymd_opt(2024, 5, 19).unwrap(),
+        gender: Gender::Male,
+        dimension: Dimension::Team,
+        metrics: vec![MetricKind::AverageBallPossession],
+        sort: vec![SortKey {
+            metric: MetricKind::AverageBallPossession,
+            order: Sort::Asc,
+        }],
+        tiebreakers: vec![],
+        ranking: Ranking::Standard,
+        offset: 0,
+        limit: 2,
+        min_matches: 0,
+        filter: None,
+    };
+
+    let result = engine.execute_teams(
+        &fixture.teams_repo,
+        &fixture.team_stats_repo,
+        &fixture.player_stats_repo,
+        &fixture.competitions_repo,
+        &query,
+    );
+
+    let result_teams: Vec<(String, f64)> = result
+        .into_iter()
+        .map(|t| (t.dimension.team_abbreviation.to_string(), t.values[0].value))
+        .collect();
+
+    let expected_teams = vec![("TOT".to_string(), 60.1), ("MCI".to_string(), 64.8)];
+
+    assert_eq!(
+        result_teams, expected_teams,
+        "The result did not match the expected output"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn team_stats_take_precedence_over_player_sums() -> Result<()> {
+    let mut fixture = Fixture::stub();
+    // Own goals of the opponents count for the team, not for its players
+    fixture.team_stats_repo.push(TeamStats {
+        team_id: "sr:competitor:33".parse().unwrap(),
+        competition_id: "sr:competition:808".parse().unwrap(),
+        metrics: vec![TeamMetric::GoalsScored { value: 74.0 }],
+    });
+    let engine = Engine;
+    let query = Query {
+        event: "Premier League".to_string(),
+        location: "England".to_string(),
+        season_start: NaiveDate::from_ymd_opt(2023, 8, 11).unwrap(),
+        season_end: NaiveDate::from_ymd_opt(2024, 5, 19).unwrap(),
+        gender: Gender::Male,
+        dimension: Dimension::Team,
+        metrics: vec![MetricKind::GoalsScored],
+        sort: vec![SortKey {
+            metric: MetricKind::GoalsScored,
+            order: Sort::Desc,
+        }],
+        tiebreakers: vec![],
+        ranking: Ranking::Standard,
+        offset: 0,
+        limit: 2,
+        min_matches: 0,
+        filter: None,
+    };
+
+    let result = engine.execute_teams(
+        &fixture.teams_repo,
+        &fixture.team_stats_repo,
+        &fixture.player_stats_repo,
+        &fixture.competitions_repo,
+        &query,
+    );
+
+    let result_teams: Vec<(String, f64)> = result
+        .into_iter()
+        .map(|t| (t.dimension.team_abbreviation.to_string(), t.values[0].value))
+        .collect();
+
+    let expected_teams = vec![("TOT".to_string(), 74.0), ("MCI".to_string(), 17.0)];
+
+    assert_eq!(
+        result_teams, expected_teams,
+        "The result did not match the expected output"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn team_ratios_divide_by_the_team_matches() {
+    // Tottenham reports its matches, Manchester City does not
+    let mut fixture = Fixture::stub();
+    fixture.team_stats_repo.push(TeamStats {
+        team_id: "sr:competitor:33".parse().unwrap(),
+        competition_id: "sr:competition:808".parse().unwrap(),
+        metrics: vec![TeamMetric::MatchesPlayed { value: 38.0 }],
+    });
+    let query = Query {
+        dimension: Dimension::Team,
+        metrics: vec![MetricKind::GoalsPerMatch, MetricKind::GoalsScored],
+        sort: vec![SortKey {
+            metric: MetricKind::GoalsScored,
+            order: Sort::Desc,
+        }],
+        ..premier_league(None)
+    };
+
+    let result = Engine.execute_teams(
+        &fixture.teams_repo,
+        &fixture.team_stats_repo,
+        &fixture.player_stats_repo,
+        &fixture.competitions_repo,
+        &query,
+    );
+
+    // The appearances of the City players do not add up to its matches
+    let result_teams: Vec<(&str, Vec<f64>)> = result
+        .iter()
+        .map(|t| {
+            let values = t.values.iter().map(|v| v.value).collect();
+            (t.dimension.team_abbreviation, values)
+        })
+        .collect();
+    assert_eq!(result_teams, vec![("TOT", vec![4.0 / 38.0, 4.0])]);
+
+    let query = Query {
+        min_matches: 30,
+        metrics: vec![MetricKind::GoalsScored],
+        ..query
+    };
+    let result = Engine.execute_teams(
+        &fixture.teams_repo,
+        &fixture.team_stats_repo,
+        &fixture.player_stats_repo,
+        &fixture.competitions_repo,
+        &query,
+    );
+    let result_teams: Vec<&str> = result
+        .iter()
+        .map(|t| t.dimension.team_abbreviation)
+        .collect();
+    assert_eq!(result_teams, vec!["TOT"]);
+}
+
+#[test]
+fn leave_out_players_without_the_metric() -> Result<()> {
+    let fixture = Fixture::stub();
+    let engine = Engine;
+    let query = Query {
+        event: "Premier League".to_string(),
+        location: "England".to_string(),
+        season_start: NaiveDate::from_ymd_opt(2023, 8, 11).unwrap(),
+        season_end: NaiveDate::from_ymd_opt(2024, 5, 19).unwrap(),
+        gender: Gender::Male,
+        dimension: Dimension::Player,
+        metrics: vec![MetricKind::Offsides],
+        sort: vec![SortKey {
+            metric: MetricKind::Offsides,
+            order: Sort::Asc,
+        }],
+        tiebreakers: vec![],
+        ranking: Ranking::Standard,
+        offset: 0,
+        limit: 10,
+        min_matches: 0,
+        filter: None,
+    };
+
+    let result = engine.execute(
+        &fixture.players_repo,
+        &fixture.teams_repo,
+        &fixture.player_stats_repo,
+        &fixture.competitions_repo,
+        &query,
+    );
+
+    // Foden has no reported offsides, rather than zero
+    let result_players: Vec<(String, f64)> = result
+        .into_iter()
+        .map(|p| (p.dimension.player_name.to_string(), p.values[0].value))
+        .collect();
+
+    let expected_players = vec![
+        ("Romero, Cristian".to_string(), 3.0),
+        ("Alvarez, Julian".to_string(), 12.0),
+    ];
+
+    assert_eq!(
+        result_players, expected_players,
+        "The result did not match the expected output"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn top_goals_per_match_with_min_matches() -> Result<()> {
+    let fixture = Fixture::stub();
+    let engine = Engine;
+    let query = Query {
+        event: "Premier League".to_string(),
+        location: "England".to_string(),
+        season_start: NaiveDate::from_ymd_opt(2023, 8, 11).unwrap(),
+        season_end: NaiveDate::from_ymd_opt(2024, 5, 19).unwrap(),
+        gender: Gender::Male,
+        dimension: Dimension::Player,
+        metrics: vec![MetricKind::GoalsPerMatch],
+        sort: vec![SortKey {
+            metric: MetricKind::GoalsPerMatch,
+            order: Sort::Desc,
+        }],
+        tiebreakers: vec![],
+        ranking: Ranking::Standard,
+        offset: 0,
+        limit: 10,
+        min_matches: 32,
+        filter: None,
+    };
+
+    let result = engine.execute(
+        &fixture.players_repo,
+        &fixture.teams_repo,
+        &fixture.player_stats_repo,
+        &fixture.competitions_repo,
+        &query,
+    );
+
+    // Alvarez scores the most per match, but played 31 matches only
+    let result_players: Vec<(String, f64)> = result
+        .into_iter()
+        .map(|p| (p.dimension.player_name.to_string(), p.values[0].value))
+        .collect();
+
+    let expected_players = vec![
+        ("Foden, Phil".to_string(), 7.0 / 35.0),
+        ("Romero, Cristian".to_string(), 4.0 / 33.0),
+    ];
+
+    assert_eq!(
+        result_players, expected_players,
+        "The result did not match the expected output"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn return_several_metrics_sorted_by_several_keys() -> Result<()> {
+    let fixture = Fixture::stub();
+    let engine = Engine;
+    let query = Query {
+        event: "Premier League".to_string(),
+        location: "England".to_string(),
+        season_start: NaiveDate::from_ymd_opt(2023, 8, 11).unwrap(),
+        season_end: NaiveDate::from_ymd_opt(2024, 5, 19).unwrap(),
+        gender: Gender::Male,
+        dimension: Dimension::Player,
+        metrics: vec![
+            MetricKind::GoalsScored,
+            MetricKind::Assists,
+            MetricKind::MatchesPlayed,
+        ],
+        sort: vec![
+            SortKey {
+                metric: MetricKind::GoalContributions,
+                order: Sort::Desc,
+            },
+            SortKey {
+                metric: MetricKind::MatchesPlayed,
+                order: Sort::Asc,
+            },
+        ],
+        tiebreakers: vec![],
+        ranking: Ranking::Standard,
+        offset: 0,
+        limit: 10,
+        min_matches: 0,
+        filter: None,
+    };
+
+    let result = engine.execute(
+        &fixture.players_repo,
+        &fixture.teams_repo,
+        &fixture.player_stats_repo,
+        &fixture.competitions_repo,
+        &query,
+    );
+
+    // Alvarez and Romero both have 13 goal contributions, Alvarez in fewer matches
+    let result_players: Vec<(String, Vec<f64>)> = result
+        .into_iter()
+        .map(|p| {
+            let values = p.values.iter().map(|v| v.value).collect();
+            (p.dimension.player_name.to_string(), values)
+        })
+        .collect();
+
+    let expected_players = vec![
+        ("Alvarez, Julian".to_string(), vec![10.0, 3.0, 31.0]),
+        ("Romero, Cristian".to_string(), vec![4.0, 9.0, 33.0]),
+        ("Foden, Phil".to_string(), vec![7.0, 5.0, 35.0]),
+    ];
+
+    assert_eq!(
+        result_players, expected_players,
+        "The result did not match the expected output"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn filter_rows_before_ranking() -> Result<()> {
+    let fixture = Fixture::stub();
+    let engine = Engine;
+    let query = Query {
+        event: "Premier League".to_string(),
+        location: "England".to_string(),
+        season_start: NaiveDate::from_ymd_opt(2023, 8, 11).unwrap(),
+        season_end: NaiveDate::from_ymd_opt(2024, 5, 19).unwrap(),
+        gender: Gender::Male,
+        dimension: Dimension::Player,
+        metrics: vec![MetricKind::Assists],
+        sort: vec![SortKey {
+            metric: MetricKind::Assists,
+            order: Sort::Desc,
+        }],
+        tiebreakers: vec![],
+        ranking: Ranking::Standard,
+        offset: 0,
+        limit: 1,
+        min_matches: 0,
+        filter: Some(
+            "goals_scored >= 5 and (team = mci or player ~ romero)"
+                .parse()
+                .unwrap(),
+        ),
+    };
+
+    let result = engine.execute(
+        &fixture.players_repo,
+        &fixture.teams_repo,
+        &fixture.player_stats_repo,
+        &fixture.competitions_repo,
+        &query,
+    );
+
+    // Romero assists the most, but scored 4 goals only
+    let result_ids: Vec<String> = result
+        .into_iter()
+        .map(|p| p.dimension.player_id.to_string())
+        .collect();
+
+    assert_eq!(
+        result_ids,
+        vec!["sr:player:1047129".to_string()],
+        "The result did not match the expected output"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn rank_players_missing_from_the_repo() {
+    // Statistics synced without their player are ranked with an empty name, not a panic
+    let fixture = Fixture {
+        players_repo: InMemoRepository::new(),
+        ..Fixture::stub()
+    };
+    let query = Query {
+        metrics: vec![MetricKind::GoalsScored],
+        sort: vec![SortKey {
+            metric: MetricKind::GoalsScored,
+            order: Sort::Desc,
+        }],
+        limit: 2,
+        ..premier_league(Some("team = mci"))
+    };
+
+    let result = Engine.execute(
+        &fixture.players_repo,
+        &fixture.teams_repo,
+        &fixture.player_stats_repo,
+        &fixture.competitions_repo,
+        &query,
+    );
+
+    let rows: Vec<(&str, &str)> = result
+        .iter()
+        .map(|row| (row.dimension.player_id, row.dimension.player_name))
+        .collect();
+    assert_eq!(
+        rows,
+        vec![("sr:player:1630398", ""), ("sr:player:1047129", "")]
+    );
+}
+
+#[test]
+fn compute_derived_metrics() {
+    let value = |kind: MetricKind| match kind {
+        MetricKind::GoalsScored => Some(12.0),
+        MetricKind::Assists => Some(5.0),
+        MetricKind::GoalsByPenalty => Some(3.0),
+        MetricKind::PenaltiesMissed => Some(1.0),
+        MetricKind::GoalsByHead => Some(0.0),
+        MetricKind::MatchesPlayed => Some(0.0),
+        _ => None,
+    };
+
+    assert_eq!(MetricKind::GoalContributions.compute(value), Some(17.0));
+    assert_eq!(MetricKind::PenaltyConversion.compute(value), Some(0.75));
+    assert_eq!(MetricKind::HeaderShare.compute(value), Some(0.0));
+    // Undefined without matches played, or without the cards reported
+    assert_eq!(MetricKind::GoalsPerMatch.compute(value), None);
+    assert_eq!(MetricKind::CardsPerMatch.compute(value), None);
+    assert_eq!(MetricKind::Assists.compute(value), Some(5.0));
+}
+
+#[test]
+fn parse_metric_kind_names() {
+    for kind in MetricKind::ALL {
+        assert_eq!(kind.name().parse::<MetricKind>().as_ref(), Ok(kind));
+    }
+    assert!(MetricKind::CornerKicks.is_team_metric());
+    assert!(!MetricKind::CornerKicks.is_player_metric());
+    assert!(MetricKind::GoalsScored.is_player_metric());
+    assert!(MetricKind::OwnGoals.is_player_metric());
+    assert!(!MetricKind::OwnGoals.is_team_metric());
+    assert!(MetricKind::GoalsPerMatch.is_player_metric());
+    assert!(MetricKind::GoalsPerMatch.is_derived());
+    assert!("corners".parse::<MetricKind>().is_err());
+}
+
+#[test]
+fn season_window() {
+    let fixture = Fixture::stub();
+    let query: Query = "top 2 players by goals in 'Premier League' England 2023/24"
+        .parse()
+        .unwrap();
+
+    let result = Engine.execute(
+        &fixture.players_repo,
+        &fixture.teams_repo,
+        &fixture.player_stats_repo,
+        &fixture.competitions_repo,
+        &query,
+    );
+
+    let result_ids: Vec<String> = result
+        .into_iter()
+        .map(|p| p.dimension.player_id.to_string())
+        .collect();
+    assert_eq!(result_ids, vec!["sr:player:1630398", "sr:player:1047129"]);
+}
+
+fn premier_league(filter: Option<&str>) -> Query {
+    Query {
+        event: "Premier League".to_string(),
+        location: "England".to_string(),
+        season_start: NaiveDate::from_ymd_opt(2023, 8, 11).unwrap(),
+        season_end: NaiveDate::from_ymd_opt(2024, 5, 19).unwrap(),
+        gender: Gender::Male,
+        dimension: Dimension::Player,
+        metrics: vec![],
+        sort: vec![],
+        tiebreakers: vec![],
+        ranking: Ranking::Standard,
+        offset: 0,
+        limit: 10,
+        min_matches: 0,
+        filter: filter.map(|filter| filter.parse().unwrap()),
+    }
+}
+
+#[test]
+fn aggregate_per_team() {
+    let fixture = Fixture::stub();
+    let query = premier_league(None);
+    let aggregation = Aggregation {
+        group_by: GroupBy::Team,
+        aggregates: vec![
+            "avg(goals_scored)".parse().unwrap(),
+            "max(assists)".parse().unwrap(),
+        ],
+        order: Sort::Desc,
+    };
+
+    let result = Engine.aggregate(
+        &fixture.players_repo,
+        &fixture.teams_repo,
+        &fixture.player_stats_repo,
+        &fixture.competitions_repo,
+        &query,
+        &aggregation,
+    );
+
+    let result: Vec<(&str, usize, Vec<f64>)> = result
+        .iter()
+        .map(|r| {
+            (
+                r.group.group_name,
+                r.players,
+                r.values.iter().map(|v| v.value).collect(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        result,
+        vec![
+            ("Manchester City", 2, vec![8.5, 5.0]),
+            ("Tottenham Hotspur", 1, vec![4.0, 9.0]),
+        ]
+    );
+}
+
+#[test]
+fn aggregate_filtered_rows() {
+    let fixture = Fixture::stub();
+    // players with at least 5 goals, per team
+    let query = premier_league(Some("goals_scored >= 5"));
+    let aggregation = Aggregation {
+        group_by: GroupBy::Team,
+        aggregates: vec!["count(goals_scored)".parse().unwrap()],
+        order: Sort::Desc,
+    };
+
+    let result = Engine.aggregate(
+        &fixture.players_repo,
+        &fixture.teams_repo,
+        &fixture.player_stats_repo,
+        &fixture.competitions_repo,
+        &query,
+        &aggregation,
+    );
+
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].group.group_id, "sr:competitor:17");
+    assert_eq!(result[0].values[0].value, 2.0);
+}
+
+#[test]
+fn aggregate_per_competition() {
+    let fixture = Fixture::stub();
+    let query = premier_league(None);
+    let aggregation = Aggregation {
+        group_by: GroupBy::Competition,
+        aggregates: vec![
+            "sum(goals_scored)".parse().unwrap(),
+            "min(goals_per_match)".parse().unwrap(),
+            // offsides are not reported for every player
+            "count(offsides)".parse().unwrap(),
+        ],
+        order: Sort::Desc,
+    };
+
+    let result = Engine.aggregate(
+        &fixture.players_repo,
+        &fixture.teams_repo,
+        &fixture.player_stats_repo,
+        &fixture.competitions_repo,
+        &query,
+        &aggregation,
+    );
+
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].group.group_name, "Premier League");
+    assert_eq!(result[0].players, 3);
+    let values: Vec<f64> = result[0].values.iter().map(|v| v.value).collect();
+    assert_eq!(values, vec![21.0, 4.0 / 33.0, 2.0]);
+}
+
+#[test]
+fn count_players_matching_a_condition() {
+    // A Manchester City player who played without scoring
+    let mut fixture = Fixture::stub();
+    fixture.players_repo.push(Player {
+        id: "sr:player:4".parse().unwrap(),
+        name: "Ortega, Stefan".to_string(),
+    });
+    fixture.player_stats_repo.push(PlayerStats {
+        player_id: "sr:player:4".parse().unwrap(),
+        team_id: "sr:competitor:17".parse().unwrap(),
+        competition_id: "sr:competition:808".parse().unwrap(),
+        metrics: vec![
+            Metric::GoalsScored { value: 0 },
+            Metric::MatchesPlayed { value: 10 },
+        ],
+    });
+    let query = premier_league(Some("team = mci"));
+    let aggregation = Aggregation {
+        group_by: GroupBy::Team,
+        aggregates: vec![
+            "count(goals_scored)".parse().unwrap(),
+            "count(goals_scored > 0)".parse().unwrap(),
+        ],
+        order: Sort::Desc,
+    };
+
+    let result = Engine.aggregate(
+        &fixture.players_repo,
+        &fixture.teams_repo,
+        &fixture.player_stats_repo,
+        &fixture.competitions_repo,
+        &query,
+        &aggregation,
+    );
+
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].players, 3);
+    let values: Vec<f64> = result[0].values.iter().map(|v| v.value).collect();
+    assert_eq!(
+        values,
+        vec![3.0, 2.0],
+        "The player without a goal is not counted"
+    );
+}
+
+#[test]
+fn parse_count_conditions() {
+    let aggregate: Aggregate = "count(goals >= 1 and assists > 0)".parse().unwrap();
+    assert_eq!(aggregate.function, AggregateFunction::Count);
+    assert_eq!(aggregate.metric, MetricKind::GoalsScored);
+    assert_eq!(
+        aggregate.to_string(),
+        "count(goals_scored >= 1 and assists > 0)"
+    );
+    assert_eq!(aggregate.to_string().parse::<Aggregate>(), Ok(aggregate));
+
+    assert!("sum(goals_scored > 0)".parse::<Aggregate>().is_err());
+    assert!("count(team = mci)".parse::<Aggregate>().is_err());
+}
+
+#[test]
+fn parse_aggregates() {
+    let aggregate: Aggregate = "avg(goals)".parse().unwrap();
+    assert_eq!(
+        aggregate,
+        Aggregate {
+            function: AggregateFunction::Avg,
+            metric: MetricKind::GoalsScored,
+            condition: None,
+        }
+    );
+    assert_eq!(aggregate.to_string(), "avg(goals_scored)");
+
+    assert!("median(goals_scored)".parse::<Aggregate>().is_err());
+    assert!("avg goals_scored".parse::<Aggregate>().is_err());
+}
+
+/// The fixture, with a player tied on goals with Alvarez (10) but fewer matches, and
+/// another tied with Foden (7) on both goals and matches.
+fn fixture_with_ties() -> Fixture {
+    let mut fixture = Fixture::stub();
+    for (id, name, team, goals, matches) in [
+        ("sr:player:4", "Haaland, Erling", "sr:competitor:17", 10, 27),
+        ("sr:player:5", "Son, Heung-min", "sr:competitor:33", 7, 35),
+    ] {
+        fixture.players_repo.push(Player {
+            id: id.parse().unwrap(),
+            name: name.to_string(),
+        });
+        fixture.player_stats_repo.push(PlayerStats {
+            player_id: id.parse().unwrap(),
+            team_id: team.parse().unwrap(),
+            competition_id: "sr:competition:808".parse().unwrap(),
+            metrics: vec![
+                Metric::GoalsScored { value: goals },
+                Metric::MatchesPlayed { value: matches },
+            ],
+        });
+    }
+    fixture
+}
+
+fn ranked(fixture: &Fixture, query: &Query) -> Vec<(u32, String)> {
+    Engine
+        .execute(
+            &fixture.players_repo,
+            &fixture.teams_repo,
+            &fixture.player_stats_repo,
+            &fixture.competitions_repo,
+            query,
+        )
+        .into_iter()
+        .map(|r| (r.rank, r.dimension.player_name.to_string()))
+        .collect()
+}
+
+fn top_scorers(ranking: Ranking, tiebreakers: &str) -> Query {
+    Query {
+        metrics: vec![MetricKind::GoalsScored],
+        sort: vec![SortKey {
+            metric: MetricKind::GoalsScored,
+            order: Sort::Desc,
+        }],
+        tiebreakers: tiebreakers
+            .split(',')
+            .filter(|tiebreaker| !tiebreaker.is_empty())
+            .map(|tiebreaker| tiebreaker.parse().unwrap())
+            .collect(),
+        ranking,
+        ..premier_league(None)
+    }
+}
+
+#[test]
+fn rank_ties() {
+    let fixture = fixture_with_ties();
+    let ranks = |ranking: Ranking| -> Vec<u32> {
+        ranked(&fixture, &top_scorers(ranking, ""))
+            .into_iter()
+            .map(|(rank, _)| rank)
+            .collect()
+    };
+
+    assert_eq!(ranks(Ranking::Standard), vec![1, 1, 3, 3, 5]);
+    assert_eq!(ranks(Ranking::Dense), vec![1, 1, 2, 2, 3]);
+    assert_eq!(ranks(Ranking::Ordinal), vec![1, 2, 3, 4, 5]);
+    // Tied rows still come in the same order, by id
+    assert_eq!(
+        ranked(&fixture, &top_scorers(Ranking::Standard, "")),
+        vec![
+            (1, "Alvarez, Julian".to_string()),
+            (1, "Haaland, Erling".to_string()),
+            (3, "Foden, Phil".to_string()),
+            (3, "Son, Heung-min".to_string()),
+            (5, "Romero, Cristian".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn break_ties() {
+    let fixture = fixture_with_ties();
+
+    assert_eq!(
+        ranked(
+            &fixture,
+            &top_scorers(Ranking::Standard, "matches_played:asc")
+        ),
+        vec![
+            (1, "Haaland, Erling".to_string()),
+            (2, "Alvarez, Julian".to_string()),
+            (3, "Foden, Phil".to_string()),
+            (3, "Son, Heung-min".to_string()),
+            (5, "Romero, Cristian".to_string()),
+        ]
+    );
+    // Names break the ties left, Foden before Son
+    let query = top_scorers(Ranking::Dense, "matches_played:asc,name");
+    let ranks: Vec<u32> = ranked(&fixture, &query)
+        .into_iter()
+        .map(|(rank, _)| rank)
+        .collect();
+    assert_eq!(ranks, vec![1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn paginate() {
+    let fixture = fixture_with_ties();
+    let query = Query {
+        offset: 1,
+        limit: 2,
+        ..top_scorers(Ranking::Standard, "")
+    };
+
+    // Ranks are counted before skipping the offset
+    assert_eq!(
+        ranked(&fixture, &query),
+        vec![
+            (1, "Haaland, Erling".to_string()),
+            (3, "Foden, Phil".to_string()),
+        ]
+    );
+}
@@ -0,0 +1,197 @@
+use engine::engine::MetricKind;
+use engine::filter::{Comparison, Predicate, TextComparison};
+use engine::parser::parse_predicate;
+
+fn goals_at_least(value: f64) -> Predicate {
+    Predicate::Metric {
+        metric: MetricKind::GoalsScored,
+        op: Comparison::Ge,
+        value,
+    }
+}
+
+fn team(value: &str) -> Predicate {
+    Predicate::Team {
+        op: TextComparison::Eq,
+        value: value.to_string(),
+    }
+}
+
+#[test]
+fn test_parse_comparisons() {
+    assert_eq!(
+        parse_predicate("goals_scored >= 10"),
+        Ok(goals_at_least(10.0))
+    );
+    assert_eq!(parse_predicate("team = LIV"), Ok(team("LIV")));
+    assert_eq!(
+        parse_predicate("player ~ 'van dijk'"),
+        Ok(Predicate::Player {
+            op: TextComparison::Contains,
+            value: "van dijk".to_string(),
+        })
+    );
+    assert_eq!(
+        parse_predicate("goals_per_match > 0.5"),
+        Ok(Predicate::Metric {
+            metric: MetricKind::GoalsPerMatch,
+            op: Comparison::Gt,
+            value: 0.5,
+        })
+    );
+}
+
+#[test]
+fn test_parse_precedence() {
+    // not binds tighter than and, which binds tighter than or
+    let predicate = parse_predicate("team = LIV or not team = ARS and goals_scored >= 10");
+
+    assert_eq!(
+        predicate,
+        Ok(Predicate::Or(
+            Box::new(team("LIV")),
+            Box::new(Predicate::And(
+                Box::new(Predicate::Not(Box::new(team("ARS")))),
+                Box::new(goals_at_least(10.0)),
+            )),
+        ))
+    );
+}
+
+#[test]
+fn test_parse_metric_short_names() {
+    assert_eq!(parse_predicate("goals >= 10"), Ok(goals_at_least(10.0)));
+    assert_eq!(
+        parse_predicate("goals+assists > 3"),
+        Ok(Predicate::Metric {
+            metric: MetricKind::GoalContributions,
+            op: Comparison::Gt,
+            value: 3.0,
+        })
+    );
+}
+
+#[test]
+fn test_parse_parentheses_and_keywords_case() {
+    let predicate = parse_predicate("(team = LIV OR team = ARS) AND goals_scored >= 10");
+
+    assert_eq!(
+        predicate,
+        Ok(Predicate::And(
+            Box::new(Predicate::Or(Box::new(team("LIV")), Box::new(team("ARS")))),
+            Box::new(goals_at_least(10.0)),
+        ))
+    );
+}
+
+#[test]
+fn test_display_round_trip() {
+    for input in [
+        "goals_scored >= 10 and team = \"LIV\"",
+        "(team = \"LIV\" or team = \"ARS\") and not goals_scored < 10",
+        "team = \"LIV\" or team = \"ARS\" and player ~ \"Sa\\\"la\"",
+        "not (assists > 3 or goals_scored != 0) and (goals_per_match <= 0.5 or (offsides = 1 or player != \"X\"))",
+    ] {
+        let predicate = parse_predicate(input).unwrap();
+        let text = predicate.to_string();
+
+        assert_eq!(text, input);
+        assert_eq!(parse_predicate(&text), Ok(predicate));
+    }
+}
+
+#[test]
+fn test_display_round_trip_any_name() {
+    let pieces = [
+        "a",
+        " ",
+        "'",
+        "\"",
+        "\\",
+        "é",
+        "日本",
+        "\u{1F600}",
+        "\t",
+        "\n",
+        "O'Neil",
+        "\\\"",
+    ];
+    // every name of up to three pieces
+    let mut names = vec![String::new()];
+    for _ in 0..3 {
+        names = names
+            .iter()
+            .flat_map(|name| pieces.iter().map(move |piece| format!("{}{}", name, piece)))
+            .chain(names.clone())
+            .collect();
+    }
+    for name in names {
+        for predicate in [
+            Predicate::Team {
+                op: TextComparison::Eq,
+                value: name.clone(),
+            },
+            Predicate::Player {
+                op: TextComparison::Contains,
+                value: name.clone(),
+            },
+        ] {
+            let text = predicate.to_string();
+            assert_eq!(parse_predicate(&text), Ok(predicate), "{}", text);
+        }
+    }
+}
+
+#[test]
+fn test_parse_negative_numbers() {
+    assert_eq!(
+        parse_predicate("goals_scored >= -2.5"),
+        Ok(goals_at_least(-2.5))
+    );
+    for value in [-1.0, -0.25, -1e-7, -123456.75, 0.0, 3.5] {
+        let predicate = goals_at_least(value);
+        let text = predicate.to_string();
+        assert_eq!(parse_predicate(&text), Ok(predicate), "{}", text);
+    }
+    assert!(parse_predicate("goals_scored >= -").is_err());
+}
+
+#[test]
+fn test_point_errors_at_the_offending_token() {
+    let error = parse_predicate("goalz >= 10").unwrap_err();
+    assert!(
+        error.message.contains("Unknown metric `goalz`"),
+        "{}",
+        error
+    );
+    assert_eq!((error.start, error.end), (0, 5));
+
+    let error = parse_predicate("goals_scored >= ten").unwrap_err();
+    assert!(error.message.contains("expected a number"), "{}", error);
+    assert_eq!((error.start, error.end), (16, 19));
+
+    let error = parse_predicate("team >= LIV").unwrap_err();
+    assert_eq!((error.start, error.end), (5, 7));
+
+    let error = parse_predicate("(team = LIV").unwrap_err();
+    assert!(error.message.contains("expected `)`"), "{}", error);
+    assert_eq!(error.start, 11);
+
+    let error = parse_predicate("team = 'LIV").unwrap_err();
+    assert!(error.message.contains("Unterminated"), "{}", error);
+}
+
+#[test]
+fn test_display_error_with_a_caret() {
+    let input = "goals_scored >= 10 and team LIV";
+    let error = parse_predicate(input).unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "Unexpected `LIV`, expected a comparison such as `>=`\n  {}\n  {}^^^",
+            input,
+            " ".repeat(28)
+        )
+    );
+}
@@ -1,12 +1,12 @@
-use std::sync::Arc;
-
 use chrono::NaiveDate;
+use engine::id::{CompetitionId, CompetitorId, PlayerId};
 use engine::repo::{
     competitions::CompetitionsRepo,
     in_memo::InMemoRepository,
-    model::{Competition, Gender, Metric, Player, PlayerStats, Team},
+    model::{Competition, Gender, Metric, Player, PlayerStats, Team, TeamMetric, TeamStats},
     player_stats::PlayerStatsRepo,
     players::PlayersRepo,
+    team_stats::TeamStatsRepo,
     teams::TeamsRepo,
 };
 
@@ -16,20 +16,21 @@ pub struct Fixture {
     pub teams_repo: TeamsRepo,
     pub players_repo: PlayersRepo,
     pub player_stats_repo: PlayerStatsRepo,
+    pub team_stats_repo: TeamStatsRepo,
 }
 
 impl Fixture {
     pub fn stub() -> Fixture {
-        let team_1_id = Arc::new("sr:competitor:17".to_string());
+        let team_1_id: CompetitorId = "sr:competitor:17".parse().unwrap();
         let team_1 = Team {
-            id: Arc::clone(&team_1_id),
+            id: team_1_id.clone(),
             name: "Manchester City".to_string(),
             abbreviation: "MCI".to_string(),
         };
 
-        let team_2_id = Arc::new("sr:competitor:33".to_string());
+        let team_2_id: CompetitorId = "sr:competitor:33".parse().unwrap();
         let team_2 = Team {
-            id: Arc::clone(&team_2_id),
+            id: team_2_id.clone(),
             name: "Tottenham Hotspur".to_string(),
             abbreviation: "TOT".to_string(),
         };
@@ -38,9 +39,9 @@ impl Fixture {
         teams_repo.push(team_1);
         teams_repo.push(team_2);
 
-        let competition_1_id = Arc::new("sr:competition:808".to_string());
+        let competition_1_id: CompetitionId = "sr:competition:808".parse().unwrap();
         let competition_1 = Competition {
-            id: Arc::clone(&competition_1_id),
+            id: competition_1_id.clone(),
             name: "Premier League".to_string(),
             location: "England".to_string(),
             gender: Gender::Male,
@@ -48,9 +49,9 @@ impl Fixture {
             season_end: NaiveDate::from_ymd_opt(2024, 5, 19).unwrap(),
         };
 
-        let competition_2_id = Arc::new("sr:competition:16".to_string());
+        let competition_2_id: CompetitionId = "sr:competition:16".parse().unwrap();
         let competition_2 = Competition {
-            id: Arc::clone(&competition_2_id),
+            id: competition_2_id.clone(),
             name: "World Cup".to_string(),
             location: "International".to_string(),
             gender: Gender::Male,
@@ -62,21 +63,21 @@ impl Fixture {
         competitions_repo.push(competition_1);
         competitions_repo.push(competition_2);
 
-        let player_1_id = Arc::new("sr:player:1630398".to_string());
+        let player_1_id: PlayerId = "sr:player:1630398".parse().unwrap();
         let player_1 = Player {
-            id: Arc::clone(&player_1_id),
+            id: player_1_id.clone(),
             name: "Alvarez, Julian".to_string(),
         };
 
-        let player_2_id = Arc::new("sr:player:1047129".to_string());
+        let player_2_id: PlayerId = "sr:player:1047129".parse().unwrap();
         let player_2 = Player {
-            id: Arc::clone(&player_2_id),
+            id: player_2_id.clone(),
             name: "Foden, Phil".to_string(),
         };
 
-        let player_3_id = Arc::new("sr:player:952278".to_string());
+        let player_3_id: PlayerId = "sr:player:952278".parse().unwrap();
         let player_3 = Player {
-            id: Arc::clone(&player_3_id),
+            id: player_3_id.clone(),
             name: "Romero, Cristian".to_string(),
         };
 
@@ -86,39 +87,44 @@ impl Fixture {
         players_repo.push(player_3);
 
         let player_stats_1_1 = PlayerStats {
-            player_id: Arc::clone(&player_1_id),
-            team_id: Arc::clone(&team_1_id),
-            competition_id: Arc::clone(&competition_1_id),
+            player_id: player_1_id.clone(),
+            team_id: team_1_id.clone(),
+            competition_id: competition_1_id.clone(),
             metrics: vec![
                 Metric::GoalsScored { value: 10 },
                 Metric::Assists { value: 3 },
+                Metric::MatchesPlayed { value: 31 },
+                Metric::Offsides { value: 12 },
             ],
         };
 
         let player_stats_2_1 = PlayerStats {
-            player_id: Arc::clone(&player_2_id),
-            team_id: Arc::clone(&team_1_id),
-            competition_id: Arc::clone(&competition_1_id),
+            player_id: player_2_id.clone(),
+            team_id: team_1_id.clone(),
+            competition_id: competition_1_id.clone(),
             metrics: vec![
                 Metric::GoalsScored { value: 7 },
                 Metric::Assists { value: 5 },
+                Metric::MatchesPlayed { value: 35 },
             ],
         };
 
         let player_stats_3_1 = PlayerStats {
-            player_id: Arc::clone(&player_3_id),
-            team_id: Arc::clone(&team_2_id),
-            competition_id: Arc::clone(&competition_1_id),
+            player_id: player_3_id.clone(),
+            team_id: team_2_id.clone(),
+            competition_id: competition_1_id.clone(),
             metrics: vec![
                 Metric::GoalsScored { value: 4 },
                 Metric::Assists { value: 9 },
+                Metric::MatchesPlayed { value: 33 },
+                Metric::Offsides { value: 3 },
             ],
         };
 
         let player_stats_1_2 = PlayerStats {
-            player_id: Arc::clone(&player_1_id),
-            team_id: Arc::clone(&team_1_id),
-            competition_id: Arc::clone(&competition_2_id),
+            player_id: player_1_id.clone(),
+            team_id: team_1_id.clone(),
+            competition_id: competition_2_id.clone(),
             metrics: vec![
                 Metric::GoalsScored { value: 8 },
                 Metric::Assists { value: 3 },
@@ -126,9 +132,9 @@ impl Fixture {
         };
 
         let player_stats_2_2 = PlayerStats {
-            player_id: Arc::clone(&player_2_id),
-            team_id: Arc::clone(&team_1_id),
-            competition_id: Arc::clone(&competition_2_id),
+            player_id: player_2_id.clone(),
+            team_id: team_1_id.clone(),
+            competition_id: competition_2_id.clone(),
             metrics: vec![
                 Metric::GoalsScored { value: 9 },
                 Metric::Assists { value: 4 },
@@ -136,9 +142,9 @@ impl Fixture {
         };
 
         let player_stats_3_2 = PlayerStats {
-            player_id: Arc::clone(&player_3_id),
-            team_id: Arc::clone(&team_2_id),
-            competition_id: Arc::clone(&competition_2_id),
+            player_id: player_3_id.clone(),
+            team_id: team_2_id.clone(),
+            competition_id: competition_2_id.clone(),
             metrics: vec![
                 Metric::GoalsScored { value: 2 },
                 Metric::Assists { value: 8 },
@@ -153,11 +159,34 @@ impl Fixture {
         player_stats_repo.push(player_stats_2_2);
         player_stats_repo.push(player_stats_3_2);
 
+        let team_stats_1_1 = TeamStats {
+            team_id: team_1_id.clone(),
+            competition_id: competition_1_id.clone(),
+            metrics: vec![
+                TeamMetric::AverageBallPossession { value: 64.8 },
+                TeamMetric::ShotsOnTarget { value: 245.0 },
+            ],
+        };
+
+        let team_stats_2_1 = TeamStats {
+            team_id: team_2_id.clone(),
+            competition_id: competition_1_id.clone(),
+            metrics: vec![
+                TeamMetric::AverageBallPossession { value: 60.1 },
+                TeamMetric::ShotsOnTarget { value: 203.0 },
+            ],
+        };
+
+        let mut team_stats_repo: TeamStatsRepo = InMemoRepository::new();
+        team_stats_repo.push(team_stats_1_1);
+        team_stats_repo.push(team_stats_2_1);
+
         Fixture {
             competitions_repo,
             teams_repo,
             players_repo,
             player_stats_repo,
+            team_stats_repo,
         }
     }
 }
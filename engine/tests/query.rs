@@ -0,0 +1,164 @@
+use chrono::NaiveDate;
+use engine::{
+    engine::*,
+    filter::{Comparison, Predicate},
+    parser::parse_query,
+    repo::model::Gender,
+};
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+#[test]
+fn test_parse_query() {
+    let query = parse_query(
+        "top 10 players by goals+assists in 'Premier League' England 2023/24 where matches >= 10",
+    );
+
+    assert_eq!(
+        query,
+        Ok(Query {
+            event: "Premier League".to_string(),
+            location: "England".to_string(),
+            season_start: date(2023, 7, 1),
+            season_end: date(2024, 6, 30),
+            gender: Gender::Male,
+            dimension: Dimension::Player,
+            metrics: vec![MetricKind::GoalContributions],
+            sort: vec![SortKey {
+                metric: MetricKind::GoalContributions,
+                order: Sort::Desc,
+            }],
+            tiebreakers: vec![],
+            ranking: Ranking::Standard,
+            offset: 0,
+            limit: 10,
+            min_matches: 0,
+            filter: Some(Predicate::Metric {
+                metric: MetricKind::MatchesPlayed,
+                op: Comparison::Ge,
+                value: 10.0,
+            }),
+        })
+    );
+}
+
+#[test]
+fn test_parse_query_clauses() {
+    let query = parse_query(
+        "BOTTOM 5 teams by corners, possession desc show shots in \"Women's Super League\" England 2023-09-01..2024-05-18 female min 3 matches",
+    )
+    .unwrap();
+
+    assert_eq!(query.dimension, Dimension::Team);
+    assert_eq!(query.limit, 5);
+    assert_eq!(
+        query.sort,
+        vec![
+            SortKey {
+                metric: MetricKind::CornerKicks,
+                order: Sort::Asc,
+            },
+            SortKey {
+                metric: MetricKind::AverageBallPossession,
+                order: Sort::Desc,
+            },
+        ]
+    );
+    assert_eq!(query.metrics, vec![MetricKind::ShotsTotal]);
+    assert_eq!(query.event, "Women's Super League");
+    assert_eq!(
+        (query.season_start, query.season_end),
+        (date(2023, 9, 1), date(2024, 5, 18))
+    );
+    assert_eq!(query.gender, Gender::Female);
+    assert_eq!(query.min_matches, 3);
+    assert_eq!(query.filter, None);
+
+    let query = parse_query(
+        "top 3 players by goals ties by matches asc, name in 'World Cup' International 2024 offset 6 rank ordinal",
+    )
+    .unwrap();
+    assert_eq!(
+        query.tiebreakers,
+        vec![
+            Tiebreaker::Metric(SortKey {
+                metric: MetricKind::MatchesPlayed,
+                order: Sort::Asc,
+            }),
+            Tiebreaker::Name,
+        ]
+    );
+    assert_eq!(query.offset, 6);
+    assert_eq!(query.ranking, Ranking::Ordinal);
+    assert_eq!(
+        (query.season_start, query.season_end),
+        (date(2024, 1, 1), date(2024, 12, 31))
+    );
+}
+
+#[test]
+fn test_display_round_trip() {
+    for input in [
+        "top 10 players by goal_contributions in \"Premier League\" England 2023/24 where matches_played >= 10",
+        "bottom 5 teams by corner_kicks, average_ball_possession desc show shots_total in \"Women's Super League\" England 2023-09-01..2024-05-18 female min 3 matches",
+        "top 3 players by goals_scored, matches_played asc show goals_scored, assists in \"World Cup\" International 2024 where team = \"LIV\" or player ~ \"saka\"",
+        "top 255 players show assists in Bundesliga Germany 1999/00",
+        "top 1000 players by goals_scored ties by matches_played asc, name show goals_scored, matches_played in \"Premier League\" England 2023/24 offset 20 rank dense where goals_scored > 0",
+        "bottom 10 teams by yellow_cards ties by name, corner_kicks desc in \"Premier League\" England 2023/24 rank ordinal",
+        "top 5 players by goals_scored in \"Ligue 1 \\\"Uber Eats\\\"\" \"Côte d'Ivoire\" 2023/24 where player = \"N'Golo \\\\ Kanté\" and goals_scored > -1",
+    ] {
+        let query: Query = input.parse().unwrap();
+        let text = query.to_string();
+
+        assert_eq!(text, input);
+        assert_eq!(text.parse(), Ok(query));
+    }
+}
+
+#[test]
+fn test_point_query_errors_at_the_offending_token() {
+    let error = parse_query("top ten players by goals in EPL England 2023/24").unwrap_err();
+    assert!(error.message.contains("the number of rows"), "{}", error);
+    assert_eq!((error.start, error.end), (4, 7));
+
+    let error =
+        parse_query("top 10 players by goals in EPL England 2023/24 rank first").unwrap_err();
+    assert!(
+        error.message.contains("`standard`, `dense` or `ordinal`"),
+        "{}",
+        error
+    );
+    assert_eq!((error.start, error.end), (52, 57));
+
+    let error = parse_query("top 10 coaches by goals in EPL England 2023/24").unwrap_err();
+    assert!(error.message.contains("`players` or `teams`"), "{}", error);
+    assert_eq!((error.start, error.end), (7, 14));
+
+    let error = parse_query("top 10 players by goalz in EPL England 2023/24").unwrap_err();
+    assert!(
+        error.message.contains("Unknown metric `goalz`"),
+        "{}",
+        error
+    );
+    assert_eq!((error.start, error.end), (18, 23));
+
+    let error = parse_query("top 10 players by goals in EPL England 2023/25").unwrap_err();
+    assert!(error.message.contains("expected the season"), "{}", error);
+    assert_eq!((error.start, error.end), (39, 46));
+
+    let error = parse_query("top 10 players by goals in EPL England 2023/24 where goals >= ten")
+        .unwrap_err();
+    assert!(error.message.contains("expected a number"), "{}", error);
+    assert_eq!((error.start, error.end), (62, 65));
+
+    let error = parse_query("top 10 players by goals in EPL England 2023/24 limit 3").unwrap_err();
+    assert!(
+        error
+            .message
+            .contains("expected `min`, `offset`, `rank`, `where` or the end"),
+        "{}",
+        error
+    );
+    assert_eq!((error.start, error.end), (47, 52));
+}
@@ -0,0 +1,290 @@
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use engine::id::CompetitionId;
+use engine::repo::{
+    in_memo::InMemoRepository,
+    model::{Competition, Gender, Metric, PlayerStats, TeamStats},
+    sqlite::SqliteStorage,
+    storage::Storage,
+};
+use engine::{engine::*, repo::model::PlayerDetails};
+use fixture::Fixture;
+use std::env;
+use std::fs;
+
+mod fixture;
+
+fn premier_league(season_start: (i32, u32, u32), season_end: (i32, u32, u32)) -> Competition {
+    Competition {
+        id: "sr:competition:808".parse().unwrap(),
+        name: "Premier League".to_string(),
+        location: "England".to_string(),
+        gender: Gender::Male,
+        season_start: NaiveDate::from_ymd_opt(season_start.0, season_start.1, season_start.2)
+            .unwrap(),
+        season_end: NaiveDate::from_ymd_opt(season_end.0, season_end.1, season_end.2).unwrap(),
+    }
+}
+
+fn save_fixture(storage: &SqliteStorage, fixture: &Fixture) -> Result<()> {
+    storage.transaction(|storage| {
+        for competition in fixture.competitions_repo.all().values() {
+            storage.save_competition(competition)?;
+        }
+        for team in fixture.teams_repo.all().values() {
+            storage.save_team(team)?;
+        }
+        for player in fixture.players_repo.all().values() {
+            storage.save_player(player)?;
+        }
+        for player_stats in fixture.player_stats_repo.all().values() {
+            let season = fixture
+                .competitions_repo
+                .find(&player_stats.competition_id)
+                .unwrap();
+            storage.save_player_stats(season, player_stats)?;
+        }
+        for team_stats in fixture.team_stats_repo.all().values() {
+            let season = fixture
+                .competitions_repo
+                .find(&team_stats.competition_id)
+                .unwrap();
+            storage.save_team_stats(season, team_stats)?;
+        }
+        Ok(())
+    })
+}
+
+#[test]
+fn test_migrate_new_database() -> Result<()> {
+    let storage = SqliteStorage::in_memory()?;
+
+    assert_eq!(storage.schema_version()?, 4);
+    assert!(storage.competitions()?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_reopen_migrated_database() -> Result<()> {
+    let path = env::temp_dir().join("sport-cli-test-reopen.sqlite");
+    let _ = fs::remove_file(&path);
+
+    let storage = SqliteStorage::open(&path)?;
+    storage.save_competition(&premier_league((2023, 8, 11), (2024, 5, 19)))?;
+    drop(storage);
+    let storage = SqliteStorage::open(&path)?;
+
+    assert_eq!(storage.schema_version()?, 4);
+    assert_eq!(storage.competitions()?.len(), 1);
+    fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn test_reject_newer_schema() -> Result<()> {
+    let path = env::temp_dir().join("sport-cli-test-newer.sqlite");
+    let _ = fs::remove_file(&path);
+    rusqlite::Connection::open(&path)?.pragma_update(None, "user_version", 99)?;
+
+    let error = SqliteStorage::open(&path).unwrap_err();
+
+    assert!(
+        error.to_string().contains("newer than the supported"),
+        "{}",
+        error
+    );
+    fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn test_run_engine_against_storage() -> Result<()> {
+    let fixture = Fixture::stub();
+    let storage = SqliteStorage::in_memory()?;
+    save_fixture(&storage, &fixture)?;
+
+    let season = storage
+        .competitions()?
+        .into_iter()
+        .find(|competition| competition.name == "Premier League")
+        .unwrap();
+    let mut loaded = Fixture::default();
+    storage.load_season(
+        &season,
+        &mut loaded.competitions_repo,
+        &mut loaded.teams_repo,
+        &mut loaded.players_repo,
+        &mut loaded.player_stats_repo,
+        &mut loaded.team_stats_repo,
+    )?;
+
+    let query = Query {
+        event: "Premier League".to_string(),
+        location: "England".to_string(),
+        season_start: NaiveDate::from_ymd_opt(2023, 8, 11).unwrap(),
+        season_end: NaiveDate::from_ymd_opt(2024, 5, 19).unwrap(),
+        gender: Gender::Male,
+        dimension: Dimension::Player,
+        metrics: vec![MetricKind::GoalsScored],
+        sort: vec![SortKey {
+            metric: MetricKind::GoalsScored,
+            order: Sort::Desc,
+        }],
+        tiebreakers: vec![],
+        ranking: Ranking::Standard,
+        offset: 0,
+        limit: 2,
+        min_matches: 0,
+        filter: None,
+    };
+    let run = |fixture: &Fixture| -> Vec<(String, f64)> {
+        let results: Vec<QueryResponse<PlayerDetails>> = Engine.execute(
+            &fixture.players_repo,
+            &fixture.teams_repo,
+            &fixture.player_stats_repo,
+            &fixture.competitions_repo,
+            &query,
+        );
+        results
+            .into_iter()
+            .map(|result| {
+                (
+                    result.dimension.player_name.to_string(),
+                    result.values[0].value,
+                )
+            })
+            .collect()
+    };
+
+    assert_eq!(run(&loaded), run(&fixture));
+    Ok(())
+}
+
+#[test]
+fn test_keep_seasons_of_a_competition_apart() -> Result<()> {
+    let fixture = Fixture::stub();
+    let storage = SqliteStorage::in_memory()?;
+    save_fixture(&storage, &fixture)?;
+    let player_stats = fixture
+        .player_stats_repo
+        .all()
+        .values()
+        .find(|stats| stats.competition_id.as_str() == "sr:competition:808")
+        .unwrap()
+        .clone();
+
+    let previous_season = premier_league((2022, 8, 5), (2023, 5, 28));
+    storage.save_competition(&previous_season)?;
+    storage.save_player_stats(
+        &previous_season,
+        &PlayerStats {
+            metrics: vec![Metric::GoalsScored { value: 99 }],
+            ..player_stats.clone()
+        },
+    )?;
+
+    let current_season = premier_league((2023, 8, 11), (2024, 5, 19));
+    assert_eq!(storage.competitions()?.len(), 3);
+    assert_eq!(storage.player_stats(&previous_season)?.len(), 1);
+    let current = storage.player_stats(&current_season)?;
+    assert!(current.iter().any(|stats| stats == &player_stats));
+    assert!(current
+        .iter()
+        .all(|stats| stats.metrics.iter().all(|m| m.value() != 99)));
+    Ok(())
+}
+
+#[test]
+fn test_replace_metrics_on_save() -> Result<()> {
+    let fixture = Fixture::stub();
+    let storage = SqliteStorage::in_memory()?;
+    save_fixture(&storage, &fixture)?;
+    let season = premier_league((2023, 8, 11), (2024, 5, 19));
+    let player_stats = storage.player_stats(&season)?.remove(0);
+
+    let updated = PlayerStats {
+        metrics: vec![Metric::Assists { value: 7 }],
+        ..player_stats.clone()
+    };
+    storage.save_player_stats(&season, &updated)?;
+
+    let stored = storage.player_stats(&season)?;
+    assert_eq!(stored.len(), fixture_stats_in(&fixture, &season.id));
+    assert!(stored.contains(&updated));
+    Ok(())
+}
+
+#[test]
+fn test_clear_season_stats() -> Result<()> {
+    let fixture = Fixture::stub();
+    let storage = SqliteStorage::in_memory()?;
+    save_fixture(&storage, &fixture)?;
+    let season = premier_league((2023, 8, 11), (2024, 5, 19));
+    let other_season = fixture
+        .competitions_repo
+        .find(&"sr:competition:16".parse().unwrap())
+        .unwrap()
+        .clone();
+    let other_stats = storage.player_stats(&other_season)?;
+    assert!(!other_stats.is_empty());
+
+    storage.clear_season_stats(&season)?;
+
+    assert!(storage.player_stats(&season)?.is_empty());
+    assert!(storage.team_stats(&season)?.is_empty());
+    assert_eq!(storage.player_stats(&other_season)?, other_stats);
+    Ok(())
+}
+
+#[test]
+fn test_save_and_load_team_stats() -> Result<()> {
+    let fixture = Fixture::stub();
+    let storage = SqliteStorage::in_memory()?;
+    save_fixture(&storage, &fixture)?;
+    let season = premier_league((2023, 8, 11), (2024, 5, 19));
+
+    let mut stored = storage.team_stats(&season)?;
+    stored.sort_by(|a, b| a.team_id.cmp(&b.team_id));
+    let mut expected: Vec<TeamStats> = fixture.team_stats_repo.all().values().cloned().collect();
+    expected.sort_by(|a, b| a.team_id.cmp(&b.team_id));
+
+    assert_eq!(stored, expected);
+    Ok(())
+}
+
+#[test]
+fn test_mark_season_synced() -> Result<()> {
+    let storage = SqliteStorage::in_memory()?;
+    let season = premier_league((2023, 8, 11), (2024, 5, 19));
+    storage.save_competition(&season)?;
+    assert_eq!(storage.synced_at(&season)?, None);
+
+    let synced_at = "2024-05-20T10:00:00Z".parse::<DateTime<Utc>>()?;
+    storage.mark_synced(&season, synced_at)?;
+
+    assert_eq!(storage.synced_at(&season)?, Some(synced_at));
+    Ok(())
+}
+
+#[test]
+fn test_rollback_failed_transaction() -> Result<()> {
+    let storage = SqliteStorage::in_memory()?;
+
+    let result = storage.transaction(|storage| {
+        storage.save_competition(&premier_league((2023, 8, 11), (2024, 5, 19)))?;
+        Err(anyhow::anyhow!("Interrupted"))
+    });
+
+    assert!(result.is_err());
+    assert!(storage.competitions()?.is_empty());
+    Ok(())
+}
+
+fn fixture_stats_in(fixture: &Fixture, competition_id: &CompetitionId) -> usize {
+    fixture
+        .player_stats_repo
+        .all()
+        .values()
+        .filter(|stats| &stats.competition_id == competition_id)
+        .count()
+}
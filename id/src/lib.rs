@@ -0,0 +1,101 @@
+use serde::de::{Deserialize, Deserializer, Error};
+use serde::ser::{Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+// --------------------------------------------------
+// Strongly typed URN identifiers, such as "sr:competition:17"
+// --------------------------------------------------
+
+/// Splits an URN of the form `<namespace>:<kind>:<id>` and validates its kind.
+fn parse_urn<'a>(urn: &'a str, kind: &str) -> Result<&'a str, String> {
+    let mut parts = urn.splitn(3, ':');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(namespace), Some(urn_kind), Some(id))
+            if !namespace.is_empty() && !id.is_empty() && !id.contains(':') =>
+        {
+            if urn_kind == kind {
+                Ok(id)
+            } else {
+                Err(format!("Invalid {} id: {} is a {} id", kind, urn, urn_kind))
+            }
+        }
+        _ => Err(format!("Invalid {} id: {} is not an urn", kind, urn)),
+    }
+}
+
+macro_rules! urn_id {
+    ($(#[$meta:meta])* $name:ident, $kind:literal) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct $name(Arc<str>);
+
+        impl $name {
+            pub const KIND: &'static str = $kind;
+
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            /// The provider specific part of the urn, such as "17" for "sr:competition:17".
+            pub fn value(&self) -> &str {
+                self.0.rsplit(':').next().unwrap_or_default()
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = String;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                parse_urn(s, Self::KIND)?;
+                Ok($name(Arc::from(s)))
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(&self.0)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let urn = String::deserialize(deserializer)?;
+                urn.parse().map_err(D::Error::custom)
+            }
+        }
+    };
+}
+
+urn_id!(
+    /// Identifies a competition, such as "sr:competition:17".
+    CompetitionId,
+    "competition"
+);
+urn_id!(
+    /// Identifies a season of a competition, such as "sr:season:105353".
+    SeasonId,
+    "season"
+);
+urn_id!(
+    /// Identifies a competitor (team), such as "sr:competitor:44".
+    CompetitorId,
+    "competitor"
+);
+urn_id!(
+    /// Identifies a player, such as "sr:player:51501".
+    PlayerId,
+    "player"
+);
+urn_id!(
+    /// Identifies a competition category, such as "sr:category:1".
+    CategoryId,
+    "category"
+);
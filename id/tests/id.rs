@@ -0,0 +1,52 @@
+use anyhow::Result;
+use id::{CompetitionId, CompetitorId, PlayerId, SeasonId};
+
+#[test]
+fn parse_valid_urns() -> Result<()> {
+    let competition: CompetitionId = "sr:competition:17".parse().unwrap();
+    let season: SeasonId = "sr:season:105353".parse().unwrap();
+
+    assert_eq!(competition.as_str(), "sr:competition:17");
+    assert_eq!(competition.value(), "17");
+    assert_eq!(season.to_string(), "sr:season:105353");
+
+    Ok(())
+}
+
+#[test]
+fn reject_urn_of_another_kind() -> Result<()> {
+    let competitor = "sr:season:105353".parse::<CompetitorId>();
+
+    assert!(competitor.is_err(), "A season id is not a competitor id");
+
+    Ok(())
+}
+
+#[test]
+fn reject_malformed_urns() -> Result<()> {
+    for urn in ["", "51501", "sr:player", "sr:player:", ":player:51501"] {
+        assert!(
+            urn.parse::<PlayerId>().is_err(),
+            "{} should be rejected",
+            urn
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn serde_roundtrip() -> Result<()> {
+    let player: PlayerId = "sr:player:51501".parse().unwrap();
+
+    let json = serde_json::to_string(&player)?;
+    assert_eq!(json, "\"sr:player:51501\"");
+
+    let decoded: PlayerId = serde_json::from_str(&json)?;
+    assert_eq!(decoded, player);
+
+    let invalid = serde_json::from_str::<PlayerId>("\"sr:competitor:44\"");
+    assert!(invalid.is_err(), "Deserialization should validate the urn");
+
+    Ok(())
+}
@@ -1,79 +1,205 @@
+use crate::concurrency::ConcurrencyController;
 use crate::config::SportRadarConfig;
+use crate::drift::SchemaDrift;
+use crate::flight::SingleFlight;
+use crate::keys::{fingerprint, KeyPool, KeyUsage};
+use crate::ledger::Ledger;
 use crate::model::{
     CompetitionsResponse, CompetitorsResponse, PlayerStatisticsResponse, SeasonsResponse,
 };
-use crate::utils::{construct_url, get_json_response};
+use crate::rate::RateLimiter;
+use crate::utils::{get_response, RequestContext};
 use anyhow::Result;
+use chrono::Utc;
+use id::{CompetitionId, CompetitorId, SeasonId};
 use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tracing::debug;
+
+/// Where the provider api is served.
+pub const BASE_URL: &str = "https://api.sportradar.com/soccer";
+/// How many times a failed request is sent again before giving up on it.
+pub const MAX_RETRIES: usize = 5;
 
 pub struct SportRadarClient {
     client: Client,
+    base_url: String,
+    max_retries: usize,
     config: SportRadarConfig,
+    keys: Arc<Mutex<KeyPool>>,
+    rate_limiter: Arc<RateLimiter>,
+    concurrency: Arc<ConcurrencyController>,
+    drift: Option<Arc<Mutex<SchemaDrift>>>,
+    ledger: Option<Arc<Ledger>>,
+    flights: SingleFlight,
 }
 
 impl SportRadarClient {
     pub fn new(config: SportRadarConfig) -> Self {
         SportRadarClient {
             client: Client::new(),
+            base_url: BASE_URL.to_string(),
+            max_retries: MAX_RETRIES,
+            keys: Arc::new(Mutex::new(KeyPool::new(config.api_keys()))),
+            rate_limiter: Arc::new(RateLimiter::with_interval(config.rate_interval())),
+            concurrency: Arc::new(ConcurrencyController::new(config.concurrency())),
             config,
+            drift: None,
+            ledger: None,
+            flights: SingleFlight::new(),
         }
     }
 
-    pub fn from_env() -> Self {
-        let config = SportRadarConfig::from_env();
-        SportRadarClient::new(config)
+    /// Sends the requests to another server than the provider, such as a proxy or a test server.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Sends a failed request again up to the given number of times, instead of `MAX_RETRIES`.
+    pub fn with_max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Records unknown and missing fields of every decoded response.
+    pub fn with_drift_detection(mut self) -> Self {
+        self.drift = Some(Arc::new(Mutex::new(SchemaDrift::default())));
+        self
+    }
+
+    /// The schema drift recorded so far, if drift detection is enabled.
+    pub fn schema_drift(&self) -> Option<SchemaDrift> {
+        self.drift
+            .as_ref()
+            .map(|drift| drift.lock().unwrap().clone())
+    }
+
+    /// Requests and rejections per api key so far.
+    pub fn key_usage(&self) -> Vec<KeyUsage> {
+        self.keys.lock().unwrap().usage(Instant::now())
+    }
+
+    /// Records every request sent to the provider in the given ledger.
+    pub fn with_ledger(mut self, ledger: Arc<Ledger>) -> Self {
+        self.ledger = Some(ledger);
+        self
+    }
+
+    /// Requests left this month across all keys, if a monthly limit and a ledger are set.
+    pub fn remaining_quota(&self) -> Result<Option<u64>> {
+        let (Some(limit), Some(ledger)) = (self.config.monthly_limit(), &self.ledger) else {
+            return Ok(None);
+        };
+        let today = Utc::now().date_naive();
+        let mut remaining = 0;
+        for key in self.config.api_keys() {
+            let used = ledger.used_in_month(&fingerprint(key), today)?;
+            remaining += limit.saturating_sub(used);
+        }
+        Ok(Some(remaining))
+    }
+
+    /// The number of requests currently allowed in flight.
+    pub fn concurrency_limit(&self) -> usize {
+        self.concurrency.limit()
+    }
+
+    /// Requests that shared an identical in-flight request instead of sending their own.
+    pub fn coalesced_requests(&self) -> u64 {
+        self.flights.coalesced()
+    }
+
+    pub fn config(&self) -> &SportRadarConfig {
+        &self.config
+    }
+
+    fn context(&self, endpoint: &'static str) -> RequestContext {
+        RequestContext {
+            endpoint,
+            format: *self.config.format(),
+            keys: Arc::clone(&self.keys),
+            rate_limiter: Arc::clone(&self.rate_limiter),
+            concurrency: Arc::clone(&self.concurrency),
+            drift: self.drift.clone(),
+            ledger: self.ledger.clone(),
+            max_retries: self.max_retries,
+        }
+    }
+
+    /// Concurrent requests for the same url share a single provider call.
+    async fn get<T>(&self, base_url: &str, endpoint: &'static str) -> Result<T>
+    where
+        T: DeserializeOwned + Serialize + Clone + Send + Sync + 'static,
+    {
+        self.flights
+            .run(base_url, || {
+                get_response(&self.client, base_url, self.context(endpoint))
+            })
+            .await
+    }
+
+    pub fn from_env() -> Result<Self> {
+        let config = SportRadarConfig::from_env()?;
+        Ok(SportRadarClient::new(config))
     }
 
     pub async fn get_competitions(&self) -> Result<CompetitionsResponse> {
         let endpoint = format!("{}.{}", "competitions", self.config.format());
         let base_url = format!(
-            "https://api.sportradar.com/soccer/{}/v4/{}/{}",
+            "{}/{}/v4/{}/{}",
+            self.base_url,
             self.config.access_level(),
             self.config.language_code(),
             endpoint
         );
-        let params = [("api_key", self.config.api_key())];
-        let url = construct_url(&base_url, &params);
-        dbg!("get_competitions: {:?}", &url);
-        get_json_response(&self.client, &url).await
+        debug!(url = %base_url, "get_competitions");
+        self.get(&base_url, "competitions").await
     }
 
-    pub async fn get_competition_seasons(&self, competition_id: &str) -> Result<SeasonsResponse> {
+    pub async fn get_competition_seasons(
+        &self,
+        competition_id: &CompetitionId,
+    ) -> Result<SeasonsResponse> {
         let endpoint = format!(
             "competitions/{}/seasons.{}",
             competition_id,
             self.config.format()
         );
         let base_url = format!(
-            "https://api.sportradar.com/soccer/{}/v4/{}/{}",
+            "{}/{}/v4/{}/{}",
+            self.base_url,
             self.config.access_level(),
             self.config.language_code(),
             endpoint
         );
-        let params = [("api_key", self.config.api_key())];
-        let url = construct_url(&base_url, &params);
-        dbg!("get_competition_seasons: {:?}", &url);
-        get_json_response(&self.client, &url).await
+        debug!(url = %base_url, "get_competition_seasons");
+        self.get(&base_url, "competition_seasons").await
     }
 
-    pub async fn get_season_competitors(&self, season_id: &str) -> Result<CompetitorsResponse> {
+    pub async fn get_season_competitors(
+        &self,
+        season_id: &SeasonId,
+    ) -> Result<CompetitorsResponse> {
         let endpoint = format!("seasons/{}/competitors.{}", season_id, self.config.format());
         let base_url = format!(
-            "https://api.sportradar.com/soccer/{}/v4/{}/{}",
+            "{}/{}/v4/{}/{}",
+            self.base_url,
             self.config.access_level(),
             self.config.language_code(),
             endpoint
         );
-        let params = [("api_key", self.config.api_key())];
-        let url: String = construct_url(&base_url, &params);
-        dbg!("get_season_competitors: {:?}", &url);
-        get_json_response(&self.client, &url).await
+        debug!(url = %base_url, "get_season_competitors");
+        self.get(&base_url, "season_competitors").await
     }
 
     pub async fn get_seasonal_competitor_statistics(
         &self,
-        season_id: &str,
-        competitor_id: &str,
+        season_id: &SeasonId,
+        competitor_id: &CompetitorId,
     ) -> Result<PlayerStatisticsResponse> {
         let endpoint = format!(
             "seasons/{}/competitors/{}/statistics.{}",
@@ -82,14 +208,13 @@ impl SportRadarClient {
             self.config.format()
         );
         let base_url = format!(
-            "https://api.sportradar.com/soccer/{}/v4/{}/{}",
+            "{}/{}/v4/{}/{}",
+            self.base_url,
             self.config.access_level(),
             self.config.language_code(),
             endpoint
         );
-        let params = [("api_key", self.config.api_key())];
-        let url = construct_url(&base_url, &params);
-        dbg!("get_seasonal_competitor_statistics: {:?}", &url);
-        get_json_response(&self.client, &url).await
+        debug!(url = %base_url, "get_seasonal_competitor_statistics");
+        self.get(&base_url, "seasonal_competitor_statistics").await
     }
 }
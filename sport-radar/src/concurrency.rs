@@ -0,0 +1,94 @@
+use std::sync::Mutex;
+use tokio::sync::Notify;
+
+// --------------------------------------------------
+// Adaptive request concurrency
+// --------------------------------------------------
+
+/// Bounds the requests in flight, additively raising the bound while requests succeed
+/// and halving it when the provider pushes back (429 or timeout), up to `max`.
+#[derive(Debug)]
+pub struct ConcurrencyController {
+    state: Mutex<ConcurrencyState>,
+    released: Notify,
+}
+
+#[derive(Debug)]
+struct ConcurrencyState {
+    limit: usize,
+    max: usize,
+    in_flight: usize,
+    successes: usize,
+}
+
+/// A slot for one request, freed when dropped.
+#[derive(Debug)]
+pub struct ConcurrencyPermit<'a> {
+    controller: &'a ConcurrencyController,
+}
+
+impl Drop for ConcurrencyPermit<'_> {
+    fn drop(&mut self) {
+        self.controller.state.lock().unwrap().in_flight -= 1;
+        self.controller.released.notify_waiters();
+    }
+}
+
+impl ConcurrencyController {
+    /// Starts with a single request in flight and grows up to `max` (at least 1).
+    pub fn new(max: usize) -> Self {
+        ConcurrencyController {
+            state: Mutex::new(ConcurrencyState {
+                limit: 1,
+                max: max.max(1),
+                in_flight: 0,
+                successes: 0,
+            }),
+            released: Notify::new(),
+        }
+    }
+
+    /// Waits until fewer requests than the current limit are in flight.
+    pub async fn acquire(&self) -> ConcurrencyPermit<'_> {
+        loop {
+            // Registered before checking, so a release in between is not missed
+            let released = self.released.notified();
+            {
+                let mut state = self.state.lock().unwrap();
+                if state.in_flight < state.limit {
+                    state.in_flight += 1;
+                    return ConcurrencyPermit { controller: self };
+                }
+            }
+            released.await;
+        }
+    }
+
+    /// Raises the limit by one after a full limit's worth of successful requests.
+    pub fn succeed(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.successes += 1;
+        if state.successes >= state.limit && state.limit < state.max {
+            state.limit += 1;
+            state.successes = 0;
+            drop(state);
+            self.released.notify_waiters();
+        }
+    }
+
+    /// Halves the limit, down to a single request in flight.
+    pub fn back_off(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.limit = (state.limit / 2).max(1);
+        state.successes = 0;
+    }
+
+    /// The current number of requests allowed in flight.
+    pub fn limit(&self) -> usize {
+        self.state.lock().unwrap().limit
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.state.lock().unwrap().in_flight
+    }
+}
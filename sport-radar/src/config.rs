@@ -1,33 +1,69 @@
+use std::collections::HashMap;
 use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
 
+use anyhow::{anyhow, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize as SerializeModel;
 use serde_derive::{Deserialize, Serialize};
 
+use crate::drift::ModelDrift;
+use crate::rate;
+
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct SportRadarConfig {
-    api_key: String,
+    api_keys: Vec<String>,
     access_level: String,
     language_code: String,
-    format: String,
+    format: Format,
+    monthly_limit: Option<u64>,
+    rate_limit: f64,
+    /// The time between two requests under the rate limit.
+    rate_interval: Duration,
+    concurrency: usize,
 }
 
 impl SportRadarConfig {
-    pub fn from_env() -> Self {
+    /// Loads the config from defaults and environment variables (including `.env`).
+    pub fn from_env() -> Result<Self> {
+        Self::load(&ConfigOptions::default())
+    }
+
+    /// Loads the config by layering, from lowest to highest precedence:
+    /// defaults, the config file (and its selected profile), environment variables and overrides.
+    pub fn load(options: &ConfigOptions) -> Result<Self> {
         dotenv::dotenv().ok();
-        let api_key = env::var("API_KEY").expect("API_KEY environment variable is not set");
-        let access_level = env::var("ACCESS_LEVEL").unwrap_or_else(|_| "trial".to_string());
-        let language_code = env::var("LANGUAGE_CODE").unwrap_or_else(|_| "en".to_string());
-        let format = env::var("FORMAT").unwrap_or_else(|_| "json".to_string());
+        let env = ConfigLayer::from_env()?;
 
-        SportRadarConfig {
-            api_key,
-            access_level,
-            language_code,
-            format,
-        }
+        let path = options
+            .path
+            .clone()
+            .or_else(|| env::var("SPORT_CLI_CONFIG").ok().map(PathBuf::from))
+            .or_else(default_config_path);
+        let profile = options
+            .profile
+            .clone()
+            .or_else(|| env::var("SPORT_CLI_PROFILE").ok());
+
+        let file = match &path {
+            Some(path) if path.exists() => ConfigFile::read(path)?,
+            Some(path) if options.path.is_some() => {
+                return Err(anyhow!("Config file not found: {}", path.display()))
+            }
+            _ => ConfigFile::default(),
+        };
+        let file = file.layer(profile.as_deref())?;
+
+        options.overrides.clone().or(env).or(file).resolve()
     }
 
-    pub fn api_key(&self) -> &str {
-        &self.api_key
+    /// The api keys, rotated when the provider rejects one for quota or rate.
+    pub fn api_keys(&self) -> &[String] {
+        &self.api_keys
     }
 
     pub fn access_level(&self) -> &str {
@@ -38,7 +74,306 @@ impl SportRadarConfig {
         &self.language_code
     }
 
-    pub fn format(&self) -> &str {
+    pub fn format(&self) -> &Format {
         &self.format
     }
+
+    /// The number of requests each api key may send per month, if limited.
+    pub fn monthly_limit(&self) -> Option<u64> {
+        self.monthly_limit
+    }
+
+    /// The number of requests sent per second at most, or 0 for no limit.
+    pub fn rate_limit(&self) -> f64 {
+        self.rate_limit
+    }
+
+    /// The time between two requests under the rate limit, zero for no limit.
+    pub fn rate_interval(&self) -> Duration {
+        self.rate_interval
+    }
+
+    /// The number of requests in flight at most, reached while the provider keeps up.
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+}
+
+// --------------------------------------------------
+// Config layers
+// --------------------------------------------------
+
+/// Where to load the config from and which settings to override (e.g. from cli flags).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigOptions {
+    pub path: Option<PathBuf>,
+    pub profile: Option<String>,
+    pub overrides: ConfigLayer,
+}
+
+/// A partial config, as found in a config file, a profile, the environment or cli flags.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigLayer {
+    /// A single key, or several keys separated by commas.
+    pub api_key: Option<String>,
+    pub api_keys: Option<Vec<String>>,
+    /// A file with one key per line.
+    pub api_key_file: Option<PathBuf>,
+    pub access_level: Option<String>,
+    pub language_code: Option<String>,
+    pub format: Option<Format>,
+    pub monthly_limit: Option<u64>,
+    pub rate_limit: Option<f64>,
+    pub concurrency: Option<usize>,
+}
+
+impl ConfigLayer {
+    pub fn from_env() -> Result<Self> {
+        let var = |key: &str| env::var(key).ok().filter(|value| !value.is_empty());
+        Ok(ConfigLayer {
+            api_key: var("API_KEY"),
+            api_keys: None,
+            api_key_file: var("API_KEY_FILE").map(PathBuf::from),
+            access_level: var("ACCESS_LEVEL"),
+            language_code: var("LANGUAGE_CODE"),
+            format: var("FORMAT")
+                .map(|format| format.parse())
+                .transpose()
+                .map_err(|e| anyhow!("FORMAT environment variable is invalid: {}", e))?,
+            monthly_limit: var("MONTHLY_LIMIT")
+                .map(|limit| limit.parse())
+                .transpose()
+                .map_err(|e| anyhow!("MONTHLY_LIMIT environment variable is invalid: {}", e))?,
+            rate_limit: var("RATE_LIMIT")
+                .map(|limit| limit.parse())
+                .transpose()
+                .map_err(|e| anyhow!("RATE_LIMIT environment variable is invalid: {}", e))?,
+            concurrency: var("CONCURRENCY")
+                .map(|concurrency| concurrency.parse())
+                .transpose()
+                .map_err(|e| anyhow!("CONCURRENCY environment variable is invalid: {}", e))?,
+        })
+    }
+
+    /// Fills the settings missing in this layer from a lower precedence one.
+    /// The api keys of the same layer are kept together.
+    pub fn or(self, lower: ConfigLayer) -> ConfigLayer {
+        let has_key =
+            self.api_key.is_some() || self.api_keys.is_some() || self.api_key_file.is_some();
+        ConfigLayer {
+            api_key: if has_key { self.api_key } else { lower.api_key },
+            api_keys: if has_key {
+                self.api_keys
+            } else {
+                lower.api_keys
+            },
+            api_key_file: if has_key {
+                self.api_key_file
+            } else {
+                lower.api_key_file
+            },
+            access_level: self.access_level.or(lower.access_level),
+            language_code: self.language_code.or(lower.language_code),
+            format: self.format.or(lower.format),
+            monthly_limit: self.monthly_limit.or(lower.monthly_limit),
+            rate_limit: self.rate_limit.or(lower.rate_limit),
+            concurrency: self.concurrency.or(lower.concurrency),
+        }
+    }
+
+    /// Applies the defaults and reads the api keys, failing when none is configured.
+    pub fn resolve(self) -> Result<SportRadarConfig> {
+        let mut api_keys = self.api_keys.unwrap_or_default();
+        if let Some(api_key) = self.api_key {
+            api_keys.extend(api_key.split(',').map(str::to_string));
+        }
+        if let Some(path) = self.api_key_file {
+            let path = expand_home(&path);
+            let content = fs::read_to_string(&path)
+                .map_err(|e| anyhow!("Failed to read api key file {}: {}", path.display(), e))?;
+            api_keys.extend(content.lines().map(str::to_string));
+        }
+        let api_keys: Vec<String> = api_keys
+            .into_iter()
+            .map(|api_key| api_key.trim().to_string())
+            .filter(|api_key| !api_key.is_empty())
+            .collect();
+        if api_keys.is_empty() {
+            return Err(anyhow!(
+                "No API key configured: set API_KEY or API_KEY_FILE, \
+                 api_key or api_key_file in the config file, or pass --api-key"
+            ));
+        }
+
+        // Rate rejections (429) are waited out anyway, so a limit only spares them
+        let rate_limit = self.rate_limit.unwrap_or(0.0);
+        let rate_interval = rate::interval(rate_limit)?;
+
+        Ok(SportRadarConfig {
+            api_keys,
+            access_level: self.access_level.unwrap_or_else(|| "trial".to_string()),
+            language_code: self.language_code.unwrap_or_else(|| "en".to_string()),
+            format: self.format.unwrap_or_default(),
+            monthly_limit: self.monthly_limit,
+            rate_limit,
+            rate_interval,
+            concurrency: self.concurrency.unwrap_or(4).max(1),
+        })
+    }
+}
+
+/// The config file: top level settings plus named profiles overriding them.
+///
+/// ```toml
+/// api_key_file = "~/.sportradar/trial.key"
+///
+/// [profiles.production]
+/// api_key_file = "~/.sportradar/production.key"
+/// access_level = "production"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigFile {
+    pub base: ConfigLayer,
+    pub profiles: HashMap<String, ConfigLayer>,
+}
+
+impl FromStr for ConfigFile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut table: toml::Table = toml::from_str(s).map_err(|e| e.to_string())?;
+        let profiles = table
+            .remove("profiles")
+            .map(|profiles| profiles.try_into())
+            .transpose()
+            .map_err(|e| e.to_string())?
+            .unwrap_or_default();
+        let base = table.try_into().map_err(|e| e.to_string())?;
+
+        Ok(ConfigFile { base, profiles })
+    }
+}
+
+impl ConfigFile {
+    pub fn read(path: &Path) -> Result<Self> {
+        fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read config file {}: {}", path.display(), e))?
+            .parse()
+            .map_err(|e| anyhow!("Invalid config file {}: {}", path.display(), e))
+    }
+
+    /// The settings of the given profile on top of the top level ones.
+    pub fn layer(self, profile: Option<&str>) -> Result<ConfigLayer> {
+        match profile {
+            None => Ok(self.base),
+            Some(name) => self
+                .profiles
+                .get(name)
+                .cloned()
+                .map(|profile| profile.or(self.base))
+                .ok_or_else(|| anyhow!("Profile not found in config file: {}", name)),
+        }
+    }
+}
+
+/// `$XDG_CONFIG_HOME/sport-cli/config.toml`, or `~/.config/sport-cli/config.toml`.
+pub fn default_config_path() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|config_dir| config_dir.join("sport-cli").join("config.toml"))
+}
+
+/// `$XDG_DATA_HOME/sport-cli`, or `~/.local/share/sport-cli`, where local data is kept.
+pub fn default_data_dir() -> Option<PathBuf> {
+    env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share"))
+        })
+        .map(|data_dir| data_dir.join("sport-cli"))
+}
+
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), env::var_os("HOME")) {
+        (Ok(relative), Some(home)) => PathBuf::from(home).join(relative),
+        _ => path.to_path_buf(),
+    }
+}
+
+// --------------------------------------------------
+// Response format
+// --------------------------------------------------
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    #[default]
+    Json,
+    Xml,
+}
+
+impl Format {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Xml => "xml",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Format::Json => "application/json",
+            Format::Xml => "application/xml",
+        }
+    }
+
+    /// Decodes a response body into the given model.
+    pub fn decode<T: DeserializeOwned>(&self, body: &str) -> Result<T> {
+        match self {
+            Format::Json => serde_json::from_str::<T>(body)
+                .map_err(|e| anyhow!("Failed to parse JSON response: {}", e)),
+            Format::Xml => quick_xml::de::from_str::<T>(body)
+                .map_err(|e| anyhow!("Failed to parse XML response: {}", e)),
+        }
+    }
+
+    /// Decodes a response body into the given model, reporting its schema drift.
+    /// Only json responses keep enough of their shape to be compared with the model.
+    pub fn decode_with_drift<T>(&self, body: &str) -> Result<(T, ModelDrift)>
+    where
+        T: DeserializeOwned + SerializeModel,
+    {
+        match self {
+            Format::Json => {
+                let received: serde_json::Value = serde_json::from_str(body)
+                    .map_err(|e| anyhow!("Failed to parse JSON response: {}", e))?;
+                let decoded = T::deserialize(&received)
+                    .map_err(|e| anyhow!("Failed to parse JSON response: {}", e))?;
+                let drift = ModelDrift::detect(&received, &decoded);
+                Ok((decoded, drift))
+            }
+            Format::Xml => Err(anyhow!(
+                "Schema drift detection only supports json responses, use --format json or FORMAT=json"
+            )),
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(Format::Json),
+            "xml" => Ok(Format::Xml),
+            _ => Err(format!("Invalid format: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.extension())
+    }
 }
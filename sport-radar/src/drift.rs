@@ -0,0 +1,173 @@
+use serde::Serialize;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+// --------------------------------------------------
+// Schema drift between the provider responses and the models
+// --------------------------------------------------
+
+/// Fields that did not line up with a model while decoding a response.
+/// Fields are reported by path, such as `competitions[].category.country_code`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelDrift {
+    /// Fields sent by the provider that the model does not know about.
+    pub unknown: BTreeSet<String>,
+    /// Fields the model serializes for every decoded item, even as null, that the provider did not send.
+    pub missing: BTreeSet<String>,
+}
+
+impl ModelDrift {
+    /// Compares the raw response with the schema of the re-serialized decoded model.
+    pub fn detect<T: Serialize>(received: &Value, decoded: &T) -> Self {
+        let mut drift = ModelDrift::default();
+        if let Ok(expected) = serde_json::to_value(decoded) {
+            drift.diff("", received, &Schema::of(&expected));
+        }
+        drift
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.unknown.is_empty() && self.missing.is_empty()
+    }
+
+    pub fn merge(&mut self, other: ModelDrift) {
+        self.unknown.extend(other.unknown);
+        self.missing.extend(other.missing);
+    }
+
+    fn diff(&mut self, path: &str, received: &Value, expected: &Schema) {
+        match (received, expected) {
+            (Value::Object(received), Schema::Object(fields)) => {
+                for (key, received_value) in received {
+                    let field = join(path, key);
+                    match fields.get(key) {
+                        Some(expected_field) => {
+                            self.diff(&field, received_value, &expected_field.schema)
+                        }
+                        None => {
+                            self.unknown.insert(field);
+                        }
+                    }
+                }
+                for (key, expected_field) in fields {
+                    if expected_field.always && !received.contains_key(key) {
+                        self.missing.insert(join(path, key));
+                    }
+                }
+            }
+            // Every item is compared with the schema of the whole list, not by position
+            (Value::Array(received), Schema::List(item)) => {
+                let field = format!("{}[]", path);
+                for received_value in received {
+                    self.diff(&field, received_value, item);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The shape of a decoded model, merged across the items of its lists.
+#[derive(Debug, Clone, PartialEq)]
+enum Schema {
+    /// A scalar, a null or an empty list: nothing to compare.
+    Any,
+    Object(BTreeMap<String, SchemaField>),
+    List(Box<Schema>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct SchemaField {
+    schema: Schema,
+    /// Whether every decoded item has the field, skipped optional fields do not.
+    always: bool,
+}
+
+impl Schema {
+    fn of(value: &Value) -> Self {
+        match value {
+            Value::Object(fields) => Schema::Object(
+                fields
+                    .iter()
+                    .map(|(key, value)| {
+                        let field = SchemaField {
+                            schema: Schema::of(value),
+                            always: true,
+                        };
+                        (key.clone(), field)
+                    })
+                    .collect(),
+            ),
+            Value::Array(items) => Schema::List(Box::new(
+                items
+                    .iter()
+                    .fold(Schema::Any, |schema, item| schema.merge(Schema::of(item))),
+            )),
+            _ => Schema::Any,
+        }
+    }
+
+    /// The union of both schemas, a field is only always there if it is in both.
+    fn merge(self, other: Schema) -> Self {
+        match (self, other) {
+            (Schema::Any, other) => other,
+            (schema, Schema::Any) => schema,
+            (Schema::Object(fields), Schema::Object(mut other)) => {
+                let mut merged: BTreeMap<_, _> = fields
+                    .into_iter()
+                    .map(|(key, field)| {
+                        let field = match other.remove(&key) {
+                            Some(other_field) => SchemaField {
+                                schema: field.schema.merge(other_field.schema),
+                                always: field.always && other_field.always,
+                            },
+                            None => SchemaField {
+                                always: false,
+                                ..field
+                            },
+                        };
+                        (key, field)
+                    })
+                    .collect();
+                for (key, other_field) in other {
+                    let field = SchemaField {
+                        always: false,
+                        ..other_field
+                    };
+                    merged.insert(key, field);
+                }
+                Schema::Object(merged)
+            }
+            (Schema::List(item), Schema::List(other)) => Schema::List(Box::new(item.merge(*other))),
+            (schema, _) => schema,
+        }
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+/// Schema drift recorded per model across several responses.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SchemaDrift {
+    pub models: BTreeMap<String, ModelDrift>,
+}
+
+impl SchemaDrift {
+    pub fn record(&mut self, model: &str, drift: ModelDrift) {
+        self.models
+            .entry(model.to_string())
+            .or_default()
+            .merge(drift);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.models.values().all(ModelDrift::is_empty)
+    }
+}
@@ -0,0 +1,112 @@
+use anyhow::{anyhow, Result};
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::OnceCell;
+
+// --------------------------------------------------
+// Single-flight coalescing of identical in-flight calls
+// --------------------------------------------------
+
+type Outcome = Result<Arc<dyn Any + Send + Sync>, Arc<anyhow::Error>>;
+
+/// The error of a coalesced call, shared by every caller that waited for it.
+/// The original error, and its type, remain reachable through `downcast_ref`.
+#[derive(Debug, Clone)]
+pub struct SharedError(Arc<anyhow::Error>);
+
+impl SharedError {
+    pub fn downcast_ref<E>(&self) -> Option<&E>
+    where
+        E: fmt::Display + fmt::Debug + Send + Sync + 'static,
+    {
+        self.0.downcast_ref::<E>()
+    }
+}
+
+impl fmt::Display for SharedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl std::error::Error for SharedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.0.source()
+    }
+}
+
+/// Shares one in-flight call, and its result, between concurrent callers asking for the same key.
+/// Once the call completes the key is forgotten, so later callers make a fresh call.
+#[derive(Debug, Default)]
+pub struct SingleFlight {
+    calls: Mutex<HashMap<String, Arc<OnceCell<Outcome>>>>,
+    coalesced: AtomicU64,
+}
+
+impl SingleFlight {
+    pub fn new() -> Self {
+        SingleFlight::default()
+    }
+
+    /// Runs `call` unless an identical call is already in flight, in which case waits for its result.
+    /// If the caller running the call is cancelled, one of the waiting callers runs it instead.
+    /// A failed call returns a [`SharedError`] to every caller.
+    pub async fn run<T, F, Fut>(&self, key: &str, call: F) -> Result<T>
+    where
+        T: Clone + Send + Sync + 'static,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let cell = {
+            let mut calls = self.calls.lock().unwrap();
+            match calls.get(key) {
+                Some(cell) => {
+                    self.coalesced.fetch_add(1, Ordering::Relaxed);
+                    Arc::clone(cell)
+                }
+                None => {
+                    let cell = Arc::new(OnceCell::new());
+                    calls.insert(key.to_string(), Arc::clone(&cell));
+                    cell
+                }
+            }
+        };
+
+        let outcome = cell
+            .get_or_init(|| async {
+                match call().await {
+                    Ok(value) => Ok(Arc::new(value) as Arc<dyn Any + Send + Sync>),
+                    Err(e) => Err(Arc::new(e)),
+                }
+            })
+            .await
+            .clone();
+
+        {
+            let mut calls = self.calls.lock().unwrap();
+            if calls
+                .get(key)
+                .is_some_and(|current| Arc::ptr_eq(current, &cell))
+            {
+                calls.remove(key);
+            }
+        }
+
+        match outcome {
+            Ok(value) => value
+                .downcast_ref::<T>()
+                .cloned()
+                .ok_or_else(|| anyhow!("Coalesced call for {} returned another type", key)),
+            Err(e) => Err(SharedError(e).into()),
+        }
+    }
+
+    /// Calls that joined an identical in-flight call instead of making their own.
+    pub fn coalesced(&self) -> u64 {
+        self.coalesced.load(Ordering::Relaxed)
+    }
+}
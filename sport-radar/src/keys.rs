@@ -0,0 +1,162 @@
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+// --------------------------------------------------
+// Api key pool
+// --------------------------------------------------
+
+/// How long a key rests after the provider rejects it for exceeding its quota (403).
+pub const QUOTA_COOLDOWN: Duration = Duration::from_secs(60 * 60);
+/// How long a key rests after the provider rejects it for exceeding its rate (429).
+pub const RATE_COOLDOWN: Duration = Duration::from_secs(1);
+
+/// Usage of a single api key, with the key itself masked.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KeyUsage {
+    pub key: String,
+    pub requests: u64,
+    pub rejections: u64,
+    pub cooling_down: bool,
+}
+
+#[derive(Debug, Clone)]
+struct KeyState {
+    key: String,
+    requests: u64,
+    rejections: u64,
+    cooldown_until: Option<Instant>,
+    /// Whether the key rests for its quota, rather than its rate.
+    out_of_quota: bool,
+}
+
+/// Hands out api keys in turn, skipping keys cooling down after a quota or rate rejection.
+#[derive(Debug, Clone)]
+pub struct KeyPool {
+    keys: Vec<KeyState>,
+    current: usize,
+}
+
+/// Returned when every key of the pool is cooling down.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeysExhausted {
+    pub retry_in: Duration,
+    /// Whether every key is out of quota, so that waiting for a rate cooldown is no use.
+    pub quota: bool,
+}
+
+impl fmt::Display for KeysExhausted {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "All API keys are exhausted, retry in {}s",
+            self.retry_in.as_secs()
+        )
+    }
+}
+
+impl std::error::Error for KeysExhausted {}
+
+impl KeyPool {
+    pub fn new(keys: &[String]) -> Self {
+        KeyPool {
+            keys: keys
+                .iter()
+                .map(|key| KeyState {
+                    key: key.clone(),
+                    requests: 0,
+                    rejections: 0,
+                    cooldown_until: None,
+                    out_of_quota: false,
+                })
+                .collect(),
+            current: 0,
+        }
+    }
+
+    /// Picks the current key, or the next available one if it is cooling down.
+    /// Returns the key index, to report a rejection, and the key.
+    pub fn acquire(&mut self, now: Instant) -> Result<(usize, String), KeysExhausted> {
+        let len = self.keys.len();
+        for offset in 0..len {
+            let index = (self.current + offset) % len;
+            let state = &mut self.keys[index];
+            if state.cooldown_until.is_none_or(|until| until <= now) {
+                state.cooldown_until = None;
+                state.out_of_quota = false;
+                state.requests += 1;
+                self.current = index;
+                return Ok((index, state.key.clone()));
+            }
+        }
+
+        let retry_in = self
+            .keys
+            .iter()
+            .filter_map(|state| state.cooldown_until)
+            .min()
+            .map_or(Duration::ZERO, |until| until.saturating_duration_since(now));
+        Err(KeysExhausted {
+            retry_in,
+            quota: self.keys.iter().all(|state| state.out_of_quota),
+        })
+    }
+
+    /// Rests the key for the given cooldown and rotates to the next one.
+    /// A cooldown as long as the quota one marks the key out of quota.
+    pub fn reject(&mut self, index: usize, cooldown: Duration, now: Instant) {
+        if let Some(state) = self.keys.get_mut(index) {
+            state.rejections += 1;
+            state.cooldown_until = Some(now + cooldown);
+            state.out_of_quota = cooldown >= QUOTA_COOLDOWN;
+            if self.current == index {
+                self.current = (index + 1) % self.keys.len();
+            }
+        }
+    }
+
+    pub fn usage(&self, now: Instant) -> Vec<KeyUsage> {
+        self.keys
+            .iter()
+            .map(|state| KeyUsage {
+                key: mask(&state.key),
+                requests: state.requests,
+                rejections: state.rejections,
+                cooling_down: state.cooldown_until.is_some_and(|until| until > now),
+            })
+            .collect()
+    }
+}
+
+/// A short hash of a key, to tell keys apart in the ledger without storing them.
+/// FNV-1a, which unlike the std hasher is stable across Rust versions.
+pub fn fingerprint(key: &str) -> String {
+    let hash = key.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+/// Keeps the first 4 characters of a key, enough to recognize it when displayed.
+pub fn mask(key: &str) -> String {
+    let visible: String = key.chars().take(4).collect();
+    format!("{}****", visible)
+}
+
+/// Hides api keys from texts that may contain request urls, such as errors.
+pub fn redact(text: &str) -> String {
+    const PARAM: &str = "api_key=";
+    let mut redacted = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(PARAM) {
+        let (before, after) = rest.split_at(start + PARAM.len());
+        redacted.push_str(before);
+        let end = after
+            .find(|c: char| c == '&' || c == ')' || c == '"' || c.is_whitespace())
+            .unwrap_or(after.len());
+        redacted.push_str(&mask(&after[..end]));
+        rest = &after[end..];
+    }
+    redacted.push_str(rest);
+    redacted
+}
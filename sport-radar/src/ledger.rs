@@ -0,0 +1,189 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// --------------------------------------------------
+// Request ledger persisted on disk
+// --------------------------------------------------
+
+/// A single request sent to the provider. Those answered consume quota whatever their status,
+/// while those failing before any response are only kept for the report.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RequestRecord {
+    pub timestamp: DateTime<Utc>,
+    /// The fingerprint of the api key used for the request, see `keys::fingerprint`.
+    pub key: String,
+    pub endpoint: String,
+    /// The response status, or 0 when no response was received.
+    pub status: u16,
+    pub latency_ms: u64,
+}
+
+impl RequestRecord {
+    /// Whether the provider received the request, as to count it against the quota.
+    pub fn consumes_quota(&self) -> bool {
+        self.status != 0
+    }
+}
+
+/// Requests per key fingerprint, year and month.
+type MonthlyUsage = HashMap<(String, i32, u32), u64>;
+
+/// Appends every request to a json lines file, one record per line.
+#[derive(Debug)]
+pub struct Ledger {
+    path: PathBuf,
+    /// Read from the file on first use, then kept up to date by `append`.
+    monthly: Mutex<Option<MonthlyUsage>>,
+}
+
+impl Ledger {
+    pub fn new(path: PathBuf) -> Self {
+        Ledger {
+            path,
+            monthly: Mutex::new(None),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(&self, record: &RequestRecord) -> Result<()> {
+        let mut monthly = self.monthly.lock().unwrap();
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|e| anyhow!("Failed to write ledger {}: {}", self.path.display(), e))?;
+        if let Some(monthly) = monthly.as_mut().filter(|_| record.consumes_quota()) {
+            *monthly.entry(month_of(record)).or_default() += 1;
+        }
+        Ok(())
+    }
+
+    pub fn records(&self) -> Result<Vec<RequestRecord>> {
+        let _guard = self.monthly.lock().unwrap();
+        self.read()
+    }
+
+    fn read(&self) -> Result<Vec<RequestRecord>> {
+        if !self.path.exists() {
+            return Ok(vec![]);
+        }
+        fs::read_to_string(&self.path)
+            .map_err(|e| anyhow!("Failed to read ledger {}: {}", self.path.display(), e))?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str(line)
+                    .map_err(|e| anyhow!("Invalid ledger {}: {}", self.path.display(), e))
+            })
+            .collect()
+    }
+
+    /// Requests consuming the quota of the key of the given fingerprint during the month of the
+    /// given day.
+    pub fn used_in_month(&self, fingerprint: &str, day: NaiveDate) -> Result<u64> {
+        let mut monthly = self.monthly.lock().unwrap();
+        if monthly.is_none() {
+            let mut usage = MonthlyUsage::new();
+            for record in self.read()?.iter().filter(|record| record.consumes_quota()) {
+                *usage.entry(month_of(record)).or_default() += 1;
+            }
+            *monthly = Some(usage);
+        }
+        let key = (fingerprint.to_string(), day.year(), day.month());
+        Ok(monthly
+            .as_ref()
+            .and_then(|usage| usage.get(&key))
+            .copied()
+            .unwrap_or(0))
+    }
+}
+
+fn month_of(record: &RequestRecord) -> (String, i32, u32) {
+    let date = record.timestamp.date_naive();
+    (record.key.clone(), date.year(), date.month())
+}
+
+fn same_month(a: NaiveDate, b: NaiveDate) -> bool {
+    a.year() == b.year() && a.month() == b.month()
+}
+
+// --------------------------------------------------
+// Quota report
+// --------------------------------------------------
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EndpointUsage {
+    pub requests: u64,
+    pub failures: u64,
+    pub total_latency_ms: u64,
+}
+
+impl EndpointUsage {
+    pub fn average_latency_ms(&self) -> u64 {
+        self.total_latency_ms
+            .checked_div(self.requests)
+            .unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KeyQuota {
+    /// Requests consuming quota, see `RequestRecord::consumes_quota`.
+    pub today: u64,
+    pub month: u64,
+    /// Every request sent, including the failed ones.
+    pub endpoints: BTreeMap<String, EndpointUsage>,
+}
+
+/// Requests per key and per endpoint for the month of the given day.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QuotaReport {
+    pub day: NaiveDate,
+    /// By key fingerprint.
+    pub keys: BTreeMap<String, KeyQuota>,
+}
+
+impl QuotaReport {
+    pub fn new(records: &[RequestRecord], day: NaiveDate) -> Self {
+        let mut keys: BTreeMap<String, KeyQuota> = BTreeMap::new();
+        for record in records {
+            let date = record.timestamp.date_naive();
+            if !same_month(date, day) {
+                continue;
+            }
+            let quota = keys.entry(record.key.clone()).or_default();
+            if record.consumes_quota() {
+                quota.month += 1;
+                if date == day {
+                    quota.today += 1;
+                }
+            }
+            let usage = quota.endpoints.entry(record.endpoint.clone()).or_default();
+            usage.requests += 1;
+            usage.total_latency_ms += record.latency_ms;
+            if !(200..300).contains(&record.status) {
+                usage.failures += 1;
+            }
+        }
+        QuotaReport { day, keys }
+    }
+
+    pub fn month_total(&self) -> u64 {
+        self.keys.values().map(|quota| quota.month).sum()
+    }
+}
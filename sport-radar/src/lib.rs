@@ -1,4 +1,12 @@
 pub mod client;
-mod config;
+pub mod concurrency;
+pub mod config;
+pub mod drift;
+pub mod flight;
+pub mod keys;
+pub mod ledger;
 pub mod model;
+pub mod rate;
+#[cfg(feature = "testing")]
+pub mod testing;
 mod utils;
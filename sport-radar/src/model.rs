@@ -1,11 +1,21 @@
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use id::{CategoryId, CompetitionId, CompetitorId, PlayerId, SeasonId};
+use serde::de::{Deserializer, MapAccess, SeqAccess, Visitor};
 use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+
+// Every model is shared by the json and xml formats.
+// In xml, scalar fields are attributes (`@name`) and lists are repeated child elements,
+// hence the `alias` on each field.
 
 // --------------------------------------------------
 // Model for the "competitions" endpoint
 // --------------------------------------------------
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CompetitionCategory {
-    pub id: String,
+    #[serde(alias = "@id")]
+    pub id: CategoryId,
+    #[serde(alias = "@name")]
     pub name: String,
 }
 
@@ -17,53 +27,72 @@ pub enum CompetitionGender {
     Women,
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Competition {
-    pub id: String,
+    #[serde(alias = "@id")]
+    pub id: CompetitionId,
+    #[serde(alias = "@name")]
     pub name: String,
+    #[serde(alias = "@gender", skip_serializing_if = "Option::is_none")]
     pub gender: Option<CompetitionGender>,
     pub category: CompetitionCategory,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct CompetitionsResponse {
-    pub generated_at: String,
+    #[serde(alias = "@generated_at")]
+    pub generated_at: DateTime<FixedOffset>,
+    #[serde(alias = "competition")]
     pub competitions: Vec<Competition>,
 }
 
 // --------------------------------------------------
 // Model for the "competition_seasons" endpoint
 // --------------------------------------------------
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Season {
-    pub id: String,
+    #[serde(alias = "@id")]
+    pub id: SeasonId,
+    #[serde(alias = "@name")]
     pub name: String,
-    pub start_date: String,
-    pub end_date: String,
+    #[serde(alias = "@start_date")]
+    pub start_date: NaiveDate,
+    #[serde(alias = "@end_date")]
+    pub end_date: NaiveDate,
+    #[serde(alias = "@year")]
     pub year: String,
-    pub competition_id: String,
+    #[serde(alias = "@competition_id")]
+    pub competition_id: CompetitionId,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct SeasonsResponse {
-    pub generated_at: String,
+    #[serde(alias = "@generated_at")]
+    pub generated_at: DateTime<FixedOffset>,
+    #[serde(alias = "season")]
     pub seasons: Vec<Season>,
 }
 
 // --------------------------------------------------
 // Model for the "season_competitors" endpoint
 // --------------------------------------------------
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Competitor {
-    pub id: String,
+    #[serde(alias = "@id")]
+    pub id: CompetitorId,
+    #[serde(alias = "@name")]
     pub name: String,
+    #[serde(alias = "@short_name")]
     pub short_name: String,
+    #[serde(alias = "@abbreviation")]
     pub abbreviation: String,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct CompetitorsResponse {
-    pub generated_at: String,
+    #[serde(alias = "@generated_at")]
+    pub generated_at: DateTime<FixedOffset>,
+    #[serde(alias = "season_competitor")]
     pub season_competitors: Vec<Competitor>,
 }
 
@@ -72,45 +101,75 @@ pub struct CompetitorsResponse {
 // --------------------------------------------------
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Sport {
+    #[serde(alias = "@id")]
     pub id: String,
+    #[serde(alias = "@name")]
     pub name: String,
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SeasonDetails {
-    pub id: String,
+    #[serde(alias = "@id")]
+    pub id: SeasonId,
+    #[serde(alias = "@name")]
     pub name: String,
-    pub start_date: String,
-    pub end_date: String,
+    #[serde(alias = "@start_date")]
+    pub start_date: NaiveDate,
+    #[serde(alias = "@end_date")]
+    pub end_date: NaiveDate,
+    #[serde(alias = "@year")]
     pub year: String,
-    pub competition_id: String,
+    #[serde(alias = "@competition_id")]
+    pub competition_id: CompetitionId,
     pub sport: Sport,
 }
 
+// A player missing some statistics is kept, with those left unreported rather than failing the
+// whole competitor. The ones the provider only sends for some players are skipped when absent,
+// while the others serialize as null so that schema drift reports them as missing.
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct PlayerStatistics {
-    pub assists: u32,
+    #[serde(alias = "@assists")]
+    pub assists: Option<u32>,
+    #[serde(alias = "@cards_given", skip_serializing_if = "Option::is_none")]
     pub cards_given: Option<u32>,
-    pub goals_by_head: u32,
-    pub goals_by_penalty: u32,
-    pub goals_conceded: u32,
-    pub goals_scored: u32,
-    pub matches_played: u32,
+    #[serde(alias = "@goals_by_head")]
+    pub goals_by_head: Option<u32>,
+    #[serde(alias = "@goals_by_penalty")]
+    pub goals_by_penalty: Option<u32>,
+    #[serde(alias = "@goals_conceded")]
+    pub goals_conceded: Option<u32>,
+    #[serde(alias = "@goals_scored")]
+    pub goals_scored: Option<u32>,
+    #[serde(alias = "@matches_played")]
+    pub matches_played: Option<u32>,
+    #[serde(alias = "@offsides", skip_serializing_if = "Option::is_none")]
     pub offsides: Option<u32>,
-    pub own_goals: u32,
-    pub penalties_missed: u32,
-    pub red_cards: u32,
+    #[serde(alias = "@own_goals")]
+    pub own_goals: Option<u32>,
+    #[serde(alias = "@penalties_missed")]
+    pub penalties_missed: Option<u32>,
+    #[serde(alias = "@red_cards")]
+    pub red_cards: Option<u32>,
+    #[serde(alias = "@shots_off_target", skip_serializing_if = "Option::is_none")]
     pub shots_off_target: Option<u32>,
-    pub substituted_in: u32,
-    pub substituted_out: u32,
-    pub yellow_cards: u32,
-    pub yellow_red_cards: u32,
+    #[serde(alias = "@substituted_in")]
+    pub substituted_in: Option<u32>,
+    #[serde(alias = "@substituted_out")]
+    pub substituted_out: Option<u32>,
+    #[serde(alias = "@yellow_cards")]
+    pub yellow_cards: Option<u32>,
+    #[serde(alias = "@yellow_red_cards")]
+    pub yellow_red_cards: Option<u32>,
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Player {
-    pub id: String,
+    #[serde(alias = "@id")]
+    pub id: PlayerId,
+    #[serde(alias = "@name")]
     pub name: String,
+    #[serde(default)]
     pub statistics: PlayerStatistics,
 }
 
@@ -122,49 +181,128 @@ pub enum CompetitorGender {
     Female,
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CompetitorDetails {
-    pub id: String,
+    #[serde(alias = "@id")]
+    pub id: CompetitorId,
+    #[serde(alias = "@name")]
     pub name: String,
+    #[serde(alias = "@country")]
     pub country: String,
+    #[serde(alias = "@country_code")]
     pub country_code: String,
+    #[serde(alias = "@abbreviation")]
     pub abbreviation: String,
+    #[serde(alias = "@gender")]
     pub gender: CompetitorGender,
     pub statistics: CompetitorStatistics,
+    #[serde(default, deserialize_with = "de_players")]
     pub players: Vec<Player>,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct CompetitorStatistics {
-    pub average_ball_possession: f32,
+    #[serde(alias = "@average_ball_possession")]
+    pub average_ball_possession: f64,
+    #[serde(alias = "@cards_given")]
     pub cards_given: u32,
+    #[serde(alias = "@corner_kicks")]
     pub corner_kicks: u32,
+    #[serde(alias = "@free_kicks")]
     pub free_kicks: u32,
+    #[serde(alias = "@goals_by_foot")]
     pub goals_by_foot: u32,
+    #[serde(alias = "@goals_by_head")]
     pub goals_by_head: u32,
+    #[serde(alias = "@goals_conceded")]
     pub goals_conceded: u32,
+    #[serde(alias = "@goals_conceded_first_half")]
     pub goals_conceded_first_half: u32,
+    #[serde(alias = "@goals_conceded_second_half")]
     pub goals_conceded_second_half: u32,
+    #[serde(alias = "@goals_scored")]
     pub goals_scored: u32,
+    #[serde(alias = "@goals_scored_first_half")]
     pub goals_scored_first_half: u32,
+    #[serde(alias = "@goals_scored_second_half")]
     pub goals_scored_second_half: u32,
+    #[serde(alias = "@matches_played")]
     pub matches_played: u32,
+    #[serde(alias = "@offsides")]
     pub offsides: u32,
+    #[serde(alias = "@penalties_missed", skip_serializing_if = "Option::is_none")]
     pub penalties_missed: Option<u32>,
+    #[serde(alias = "@red_cards")]
     pub red_cards: u32,
+    #[serde(alias = "@shots_blocked")]
     pub shots_blocked: u32,
+    #[serde(alias = "@shots_off_target")]
     pub shots_off_target: u32,
+    #[serde(alias = "@shots_on_bar")]
     pub shots_on_bar: u32,
+    #[serde(alias = "@shots_on_post")]
     pub shots_on_post: u32,
+    #[serde(alias = "@shots_on_target")]
     pub shots_on_target: u32,
+    #[serde(alias = "@shots_total")]
     pub shots_total: u32,
+    #[serde(alias = "@yellow_cards")]
     pub yellow_cards: u32,
+    #[serde(alias = "@yellow_red_cards")]
     pub yellow_red_cards: u32,
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PlayerStatisticsResponse {
-    pub generated_at: String,
+    #[serde(alias = "@generated_at")]
+    pub generated_at: DateTime<FixedOffset>,
     pub season: SeasonDetails,
     pub competitor: CompetitorDetails,
 }
+
+// --------------------------------------------------
+// Format specific decoders
+// --------------------------------------------------
+
+/// Decodes the competitor players either from a json array
+/// or from the xml `<players><player/>...</players>` wrapper.
+fn de_players<'de, D>(deserializer: D) -> Result<Vec<Player>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct PlayersVisitor;
+
+    impl<'de> Visitor<'de> for PlayersVisitor {
+        type Value = Vec<Player>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a list of players")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut players = vec![];
+            while let Some(player) = seq.next_element()? {
+                players.push(player);
+            }
+            Ok(players)
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut players = vec![];
+            while let Some(key) = map.next_key::<String>()? {
+                if key == "player" {
+                    players = map.next_value()?;
+                } else {
+                    map.next_value::<serde::de::IgnoredAny>()?;
+                }
+            }
+            Ok(players)
+        }
+
+        fn visit_unit<E>(self) -> Result<Self::Value, E> {
+            Ok(vec![])
+        }
+    }
+
+    deserializer.deserialize_any(PlayersVisitor)
+}
@@ -0,0 +1,61 @@
+use anyhow::{anyhow, Result};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::{sleep_until, Instant};
+
+// --------------------------------------------------
+// Request rate limiting
+// --------------------------------------------------
+
+/// Spaces requests evenly so that at most `rate_limit` are sent per second.
+#[derive(Debug)]
+pub struct RateLimiter {
+    interval: Duration,
+    next_slot: Mutex<Option<Instant>>,
+}
+
+/// The time between two requests under the given requests per second, zero for no limit.
+/// Fails for a negative rate, or one too low to space requests by.
+pub fn interval(rate_limit: f64) -> Result<Duration> {
+    if rate_limit == 0.0 {
+        return Ok(Duration::ZERO);
+    }
+    if rate_limit > 0.0 {
+        if let Ok(interval) = Duration::try_from_secs_f64(1.0 / rate_limit) {
+            return Ok(interval);
+        }
+    }
+    Err(anyhow!(
+        "Invalid rate limit {}, expected requests per second such as 1 or 0.5, or 0 for no limit",
+        rate_limit
+    ))
+}
+
+impl RateLimiter {
+    /// A limiter for the given requests per second, or no limit if it is 0.
+    pub fn new(rate_limit: f64) -> Result<Self> {
+        Ok(Self::with_interval(interval(rate_limit)?))
+    }
+
+    /// A limiter spacing requests by the given interval, or no limit if it is zero.
+    pub fn with_interval(interval: Duration) -> Self {
+        RateLimiter {
+            interval,
+            next_slot: Mutex::new(None),
+        }
+    }
+
+    /// Waits for the next free slot.
+    pub async fn acquire(&self) {
+        let now = Instant::now();
+        let slot = {
+            let mut next_slot = self.next_slot.lock().unwrap();
+            let slot = next_slot.map_or(now, |next| next.max(now));
+            *next_slot = Some(slot + self.interval);
+            slot
+        };
+        if slot > now {
+            sleep_until(slot).await;
+        }
+    }
+}
@@ -1,10 +1,24 @@
-use std::time::Duration;
+use std::any::type_name;
+use std::fmt;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, Result};
-use reqwest::Client;
+use chrono::Utc;
+use reqwest::{Client, StatusCode};
 use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::concurrency::ConcurrencyController;
+use crate::config::Format;
+use crate::drift::SchemaDrift;
+use crate::keys::{fingerprint, mask, redact, KeyPool, QUOTA_COOLDOWN, RATE_COOLDOWN};
+use crate::ledger::{Ledger, RequestRecord};
+use crate::rate::RateLimiter;
 use tokio_retry::strategy::{jitter, ExponentialBackoff};
 use tokio_retry::RetryIf;
+use tracing::{info, info_span, warn, Instrument};
 
 pub fn construct_url(base_url: &str, params: &[(&str, &str)]) -> String {
     let query_params: String = params
@@ -16,53 +30,192 @@ pub fn construct_url(base_url: &str, params: &[(&str, &str)]) -> String {
     format!("{}?{}", base_url, query_params)
 }
 
-pub async fn get_json_response<T: DeserializeOwned>(client: &Client, url: &str) -> Result<T> {
-    let retry_backoff_strategy =
-        ExponentialBackoff::from_millis(100).max_delay(jitter(Duration::from_secs(60)));
+/// The model name used in schema drift reports, such as "SeasonsResponse".
+pub fn model_name<T>() -> &'static str {
+    type_name::<T>().rsplit("::").next().unwrap_or_default()
+}
+
+/// A failed attempt worth retrying: the request did not reach the provider, the provider failed,
+/// or it rejected the api key, which has been rotated. Any other failure would fail again,
+/// spending quota for nothing.
+#[derive(Debug)]
+struct Transient(String);
+
+impl fmt::Display for Transient {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Transient {}
+
+/// The client state shared by every request to an endpoint.
+#[derive(Debug, Clone)]
+pub struct RequestContext {
+    pub endpoint: &'static str,
+    pub format: Format,
+    pub keys: Arc<Mutex<KeyPool>>,
+    pub rate_limiter: Arc<RateLimiter>,
+    pub concurrency: Arc<ConcurrencyController>,
+    pub drift: Option<Arc<Mutex<SchemaDrift>>>,
+    pub ledger: Option<Arc<Ledger>>,
+    pub max_retries: usize,
+}
+
+impl RequestContext {
+    fn record(&self, api_key: &str, status: u16, started_at: Instant) {
+        if let Some(ledger) = &self.ledger {
+            let record = RequestRecord {
+                timestamp: Utc::now(),
+                key: fingerprint(api_key),
+                endpoint: self.endpoint.to_string(),
+                status,
+                latency_ms: started_at.elapsed().as_millis() as u64,
+            };
+            if let Err(e) = ledger.append(&record) {
+                warn!(error = %e, "Failed to record request");
+            }
+        }
+    }
+}
+
+pub async fn get_response<T: DeserializeOwned + Serialize>(
+    client: &Client,
+    base_url: &str,
+    context: RequestContext,
+) -> Result<T> {
+    let retry_backoff_strategy = ExponentialBackoff::from_millis(100)
+        .max_delay(jitter(Duration::from_secs(60)))
+        .take(context.max_retries);
 
-    // TODO! add max retries
     let retry_condition = |e: &anyhow::Error| {
-        // Retry if within retry limit
-        print!("Retrying... Request failed: {}", e);
+        // Retry transient failures within the retry limit, never once every api key is out of quota
+        if !e.is::<Transient>() {
+            return false;
+        }
+        warn!(endpoint = context.endpoint, error = %e, "Retrying request");
         true
     };
 
+    let attempts = AtomicU32::new(0);
     let retry_action = || {
         let client = Client::clone(client);
-        let url = url.to_string();
+        let context = context.clone();
+        let attempt = attempts.fetch_add(1, Ordering::Relaxed);
+        let span = info_span!("http_request", endpoint = context.endpoint, attempt);
         async move {
+            let _permit = context.concurrency.acquire().await;
+            context.rate_limiter.acquire().await;
+            let (key_index, api_key) = loop {
+                let acquired = context.keys.lock().unwrap().acquire(Instant::now());
+                match acquired {
+                    Ok(key) => break key,
+                    // Wait for a key resting after a rate rejection, rather than failing
+                    Err(exhausted) if !exhausted.quota => {
+                        info!(
+                            retry_in_ms = exhausted.retry_in.as_millis() as u64,
+                            "Waiting for an api key"
+                        );
+                        tokio::time::sleep(exhausted.retry_in).await;
+                    }
+                    Err(exhausted) => return Err(exhausted.into()),
+                }
+            };
+            let url = construct_url(base_url, &[("api_key", &api_key)]);
+            let started_at = Instant::now();
             let response = client
                 .get(&url)
-                .header("accept", "application/json")
+                .header("accept", context.format.content_type())
                 .timeout(Duration::from_secs(60))
                 .send()
                 .await
                 .map_err(|e| {
+                    context.record(&api_key, 0, started_at);
+                    if e.is_timeout() {
+                        context.concurrency.back_off();
+                    }
                     let error_msg = format!(
                         "[{}] Failed to send request: {}",
                         e.status()
                             .map(|s| s.to_string())
                             .unwrap_or_else(|| "999".to_string()),
-                        e
+                        redact(&e.to_string())
+                    );
+                    warn!(
+                        latency_ms = started_at.elapsed().as_millis() as u64,
+                        "{}", error_msg
                     );
-                    eprintln!("{}", error_msg);
-                    anyhow!(error_msg)
+                    anyhow::Error::new(Transient(error_msg))
                 })?;
 
             let status = response.status();
-            let response_text = response
-                .text()
-                .await
-                .map_err(|e| anyhow!("[{}] Failed to read response body: {}", status, e))?;
-
-            if status.is_success() {
-                // TODO! try to use response.json::<T>().await.map_err(...)
-                serde_json::from_str::<T>(&response_text)
-                    .map_err(|e| anyhow!("[{}] Failed to parse JSON response: {}", status, e))
-            } else {
-                Err(anyhow!("[{}] Error response: {}", status, response_text))
+            context.record(&api_key, status.as_u16(), started_at);
+            info!(
+                status = status.as_u16(),
+                latency_ms = started_at.elapsed().as_millis() as u64,
+                key = %mask(&api_key),
+                "Response received"
+            );
+            let response_text = response.text().await.map_err(|e| {
+                Transient(format!(
+                    "[{}] Failed to read response body: {}",
+                    status,
+                    redact(&e.to_string())
+                ))
+            })?;
+
+            // Fewer requests in flight when the provider cannot keep up
+            match status {
+                StatusCode::TOO_MANY_REQUESTS => context.concurrency.back_off(),
+                status if status.is_success() => context.concurrency.succeed(),
+                _ => {}
+            }
+
+            // Rotate to the next api key when this one runs out of quota or rate
+            let cooldown = match status {
+                StatusCode::FORBIDDEN => Some(QUOTA_COOLDOWN),
+                StatusCode::TOO_MANY_REQUESTS => Some(RATE_COOLDOWN),
+                _ => None,
+            };
+            if let Some(cooldown) = cooldown {
+                context
+                    .keys
+                    .lock()
+                    .unwrap()
+                    .reject(key_index, cooldown, Instant::now());
+                return Err(Transient(format!(
+                    "[{}] API key {} rejected: {}",
+                    status,
+                    mask(&api_key),
+                    response_text
+                ))
+                .into());
+            }
+
+            if status.is_server_error() {
+                return Err(
+                    Transient(format!("[{}] Error response: {}", status, response_text)).into(),
+                );
+            }
+            if !status.is_success() {
+                return Err(anyhow!("[{}] Error response: {}", status, response_text));
+            }
+
+            let format = context.format;
+            match context.drift {
+                None => format
+                    .decode::<T>(&response_text)
+                    .map_err(|e| anyhow!("[{}] {}", status, e)),
+                Some(drift) => {
+                    let (decoded, model_drift) = format
+                        .decode_with_drift::<T>(&response_text)
+                        .map_err(|e| anyhow!("[{}] {}", status, e))?;
+                    drift.lock().unwrap().record(model_name::<T>(), model_drift);
+                    Ok(decoded)
+                }
             }
         }
+        .instrument(span)
     };
 
     RetryIf::spawn(retry_backoff_strategy, retry_action, retry_condition).await
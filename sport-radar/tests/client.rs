@@ -1,9 +1,22 @@
 use anyhow::Result;
 use sport_radar::client::SportRadarClient;
+use sport_radar::config::{ConfigLayer, ConfigOptions, Format, SportRadarConfig};
+
+/// A client for the provider, asking for responses in the given format.
+fn client_in(format: Format) -> Result<SportRadarClient> {
+    let options = ConfigOptions {
+        overrides: ConfigLayer {
+            format: Some(format),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    Ok(SportRadarClient::new(SportRadarConfig::load(&options)?))
+}
 
 #[tokio_macros::test]
 async fn test_get_competitions() -> Result<()> {
-    let client = SportRadarClient::from_env();
+    let client = SportRadarClient::from_env()?;
 
     let competitions = client.get_competitions().await;
 
@@ -14,9 +27,11 @@ async fn test_get_competitions() -> Result<()> {
 
 #[tokio_macros::test]
 async fn test_get_competition_seasons() -> Result<()> {
-    let client = SportRadarClient::from_env();
+    let client = SportRadarClient::from_env()?;
 
-    let seasons = client.get_competition_seasons("sr:competition:17").await;
+    let seasons = client
+        .get_competition_seasons(&"sr:competition:17".parse().unwrap())
+        .await;
 
     assert!(seasons.is_ok(), "Seasons api call failed");
 
@@ -25,9 +40,11 @@ async fn test_get_competition_seasons() -> Result<()> {
 
 #[tokio_macros::test]
 async fn test_get_season_competitors() -> Result<()> {
-    let client = SportRadarClient::from_env();
+    let client = SportRadarClient::from_env()?;
 
-    let competitors = client.get_season_competitors("sr:season:105353").await;
+    let competitors = client
+        .get_season_competitors(&"sr:season:105353".parse().unwrap())
+        .await;
 
     assert!(competitors.is_ok(), "Competitors api call failed");
 
@@ -36,13 +53,80 @@ async fn test_get_season_competitors() -> Result<()> {
 
 #[tokio_macros::test]
 async fn test_get_seasonal_competitor_statistics() -> Result<()> {
-    let client = SportRadarClient::from_env();
+    let client = SportRadarClient::from_env()?;
 
     let statistics = client
-        .get_seasonal_competitor_statistics("sr:season:105353", "sr:competitor:44")
+        .get_seasonal_competitor_statistics(
+            &"sr:season:105353".parse().unwrap(),
+            &"sr:competitor:44".parse().unwrap(),
+        )
         .await;
 
     assert!(statistics.is_ok(), "Player statistics api call failed");
 
     Ok(())
 }
+
+// The xml fixtures mirror the json ones, so only the live responses tell whether the models
+// decode what the provider actually sends in xml.
+
+#[tokio_macros::test]
+async fn test_decode_live_xml_competitions() -> Result<()> {
+    let json = client_in(Format::Json)?.get_competitions().await?;
+    let xml = client_in(Format::Xml)?.get_competitions().await?;
+
+    assert!(!xml.competitions.is_empty());
+    assert_eq!(xml.competitions, json.competitions);
+
+    Ok(())
+}
+
+#[tokio_macros::test]
+async fn test_decode_live_xml_competition_seasons() -> Result<()> {
+    let competition_id = "sr:competition:17".parse().unwrap();
+    let json = client_in(Format::Json)?
+        .get_competition_seasons(&competition_id)
+        .await?;
+    let xml = client_in(Format::Xml)?
+        .get_competition_seasons(&competition_id)
+        .await?;
+
+    assert!(!xml.seasons.is_empty());
+    assert_eq!(xml.seasons, json.seasons);
+
+    Ok(())
+}
+
+#[tokio_macros::test]
+async fn test_decode_live_xml_season_competitors() -> Result<()> {
+    let season_id = "sr:season:105353".parse().unwrap();
+    let json = client_in(Format::Json)?
+        .get_season_competitors(&season_id)
+        .await?;
+    let xml = client_in(Format::Xml)?
+        .get_season_competitors(&season_id)
+        .await?;
+
+    assert!(!xml.season_competitors.is_empty());
+    assert_eq!(xml.season_competitors, json.season_competitors);
+
+    Ok(())
+}
+
+#[tokio_macros::test]
+async fn test_decode_live_xml_seasonal_competitor_statistics() -> Result<()> {
+    let season_id = "sr:season:105353".parse().unwrap();
+    let competitor_id = "sr:competitor:44".parse().unwrap();
+    let json = client_in(Format::Json)?
+        .get_seasonal_competitor_statistics(&season_id, &competitor_id)
+        .await?;
+    let xml = client_in(Format::Xml)?
+        .get_seasonal_competitor_statistics(&season_id, &competitor_id)
+        .await?;
+
+    assert!(!xml.competitor.players.is_empty());
+    assert_eq!(xml.season, json.season);
+    assert_eq!(xml.competitor, json.competitor);
+
+    Ok(())
+}
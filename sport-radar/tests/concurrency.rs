@@ -0,0 +1,74 @@
+use sport_radar::concurrency::ConcurrencyController;
+use std::time::Duration;
+
+#[test]
+fn test_raise_limit_while_requests_succeed() {
+    let controller = ConcurrencyController::new(3);
+    assert_eq!(controller.limit(), 1);
+
+    controller.succeed();
+    assert_eq!(controller.limit(), 2);
+
+    controller.succeed();
+    controller.succeed();
+    assert_eq!(controller.limit(), 3);
+
+    // Never above the configured concurrency
+    for _ in 0..10 {
+        controller.succeed();
+    }
+    assert_eq!(controller.limit(), 3);
+}
+
+#[test]
+fn test_halve_limit_on_back_off() {
+    let controller = ConcurrencyController::new(8);
+    for _ in 0..20 {
+        controller.succeed();
+    }
+    assert_eq!(controller.limit(), 6);
+
+    controller.back_off();
+    assert_eq!(controller.limit(), 3);
+    controller.back_off();
+    controller.back_off();
+    assert_eq!(controller.limit(), 1);
+}
+
+#[test]
+fn test_at_least_one_request_in_flight() {
+    let controller = ConcurrencyController::new(0);
+
+    controller.succeed();
+
+    assert_eq!(controller.limit(), 1);
+}
+
+#[tokio_macros::test]
+async fn test_wait_for_a_free_slot() {
+    let controller = ConcurrencyController::new(2);
+
+    let permit = controller.acquire().await;
+    assert_eq!(controller.in_flight(), 1);
+    let blocked = tokio::time::timeout(Duration::from_millis(50), controller.acquire()).await;
+    assert!(blocked.is_err());
+
+    drop(permit);
+    let _permit = controller.acquire().await;
+    assert_eq!(controller.in_flight(), 1);
+}
+
+#[tokio_macros::test]
+async fn test_raise_limit_wakes_waiting_requests() {
+    let controller = ConcurrencyController::new(2);
+    let _first = controller.acquire().await;
+
+    let (second, _) = tokio::join!(controller.acquire(), async {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        controller.succeed();
+    });
+
+    assert_eq!(controller.limit(), 2);
+    assert_eq!(controller.in_flight(), 2);
+    drop(second);
+}
@@ -0,0 +1,146 @@
+use anyhow::Result;
+use sport_radar::config::{ConfigFile, ConfigLayer, Format};
+use std::{env, fs};
+
+const CONFIG_FILE: &str = r#"
+api_key = "file-key"
+language_code = "es"
+
+[profiles.production]
+api_key = "production-key"
+access_level = "production"
+format = "xml"
+"#;
+
+#[test]
+fn test_resolve_defaults() -> Result<()> {
+    let layer = ConfigLayer {
+        api_key: Some("key".to_string()),
+        ..Default::default()
+    };
+    let config = layer.resolve()?;
+
+    assert_eq!(config.api_keys(), ["key"]);
+    assert_eq!(config.access_level(), "trial");
+    assert_eq!(config.language_code(), "en");
+    assert_eq!(config.format(), &Format::Json);
+    assert_eq!(config.concurrency(), 4);
+    assert_eq!(config.rate_limit(), 0.0, "No rate limit unless asked for");
+    Ok(())
+}
+
+#[test]
+fn test_resolve_invalid_rate_limit() -> Result<()> {
+    let layer = ConfigLayer {
+        api_key: Some("key".to_string()),
+        rate_limit: Some(-1.0),
+        ..Default::default()
+    };
+
+    let error = layer
+        .resolve()
+        .expect_err("Negative rate limit")
+        .to_string();
+    assert!(error.contains("Invalid rate limit -1"), "{}", error);
+    Ok(())
+}
+
+#[test]
+fn test_reject_invalid_format_env() {
+    // The only test of this binary reading the environment, so it cannot race another one
+    env::set_var("FORMAT", "yaml");
+    let layer = ConfigLayer::from_env();
+    env::remove_var("FORMAT");
+
+    let error = layer.unwrap_err().to_string();
+    assert!(
+        error.contains("FORMAT environment variable is invalid"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn test_resolve_without_api_key() -> Result<()> {
+    let config = ConfigLayer::default().resolve();
+
+    let error = config.expect_err("An api key is required").to_string();
+    assert!(error.contains("No API key configured"), "{}", error);
+    Ok(())
+}
+
+#[test]
+fn test_file_profile_overrides_top_level() -> Result<()> {
+    let file: ConfigFile = CONFIG_FILE.parse().unwrap();
+
+    let base = file.clone().layer(None)?.resolve()?;
+    assert_eq!(base.api_keys(), ["file-key"]);
+    assert_eq!(base.language_code(), "es");
+
+    let production = file.clone().layer(Some("production"))?.resolve()?;
+    assert_eq!(production.api_keys(), ["production-key"]);
+    assert_eq!(production.access_level(), "production");
+    assert_eq!(production.language_code(), "es");
+    assert_eq!(production.format(), &Format::Xml);
+
+    assert!(file.layer(Some("staging")).is_err(), "Unknown profile");
+    Ok(())
+}
+
+#[test]
+fn test_higher_layer_wins() -> Result<()> {
+    let file: ConfigFile = CONFIG_FILE.parse().unwrap();
+    let flags = ConfigLayer {
+        access_level: Some("production".to_string()),
+        ..Default::default()
+    };
+    let env = ConfigLayer {
+        api_key: Some("env-key".to_string()),
+        access_level: Some("trial".to_string()),
+        ..Default::default()
+    };
+
+    let config = flags.or(env).or(file.layer(None)?).resolve()?;
+
+    assert_eq!(config.api_keys(), ["env-key"]);
+    assert_eq!(config.access_level(), "production");
+    assert_eq!(config.language_code(), "es");
+    Ok(())
+}
+
+#[test]
+fn test_api_key_file() -> Result<()> {
+    let path = env::temp_dir().join("sport-cli-test-api-key");
+    fs::write(&path, "secret-key\n")?;
+    let layer = ConfigLayer {
+        api_key_file: Some(path.clone()),
+        ..Default::default()
+    };
+
+    let config = layer.resolve()?;
+    fs::remove_file(path)?;
+
+    assert_eq!(config.api_keys(), ["secret-key"]);
+    Ok(())
+}
+
+#[test]
+fn test_reject_unknown_settings() -> Result<()> {
+    let file = "api_kye = \"typo\"".parse::<ConfigFile>();
+
+    assert!(file.is_err(), "Unknown settings should be rejected");
+    Ok(())
+}
+
+#[test]
+fn test_resolve_several_api_keys() -> Result<()> {
+    let file: ConfigFile = "api_keys = [\"key-1\", \"key-2\"]".parse().unwrap();
+    let flags = ConfigLayer {
+        api_key: Some("key-3, key-4".to_string()),
+        ..Default::default()
+    };
+
+    assert_eq!(file.layer(None)?.resolve()?.api_keys(), ["key-1", "key-2"]);
+    assert_eq!(flags.resolve()?.api_keys(), ["key-3", "key-4"]);
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use serde_json::json;
+use sport_radar::drift::ModelDrift;
+
+#[test]
+fn test_compare_items_with_the_schema_of_their_list() {
+    // Items decoded in another order must not be matched by position,
+    // and a field skipped for some items is not missing from the others
+    let received = json!({ "players": [{ "id": 1, "offsides": 2 }, { "id": 2 }] });
+    let decoded = json!({ "players": [{ "id": 2 }, { "id": 1, "offsides": 2 }] });
+
+    let drift = ModelDrift::detect(&received, &decoded);
+
+    assert!(drift.is_empty(), "{:?}", drift);
+}
+
+#[test]
+fn test_detect_fields_missing_from_some_items() {
+    // Fields the model expects serialize as null when the provider left them out
+    let received = json!([{ "id": 1, "goals": 2 }, { "id": 2 }]);
+    let decoded = json!([{ "id": 1, "goals": 2 }, { "id": 2, "goals": null }]);
+
+    let drift = ModelDrift::detect(&received, &decoded);
+
+    assert!(drift.unknown.is_empty(), "{:?}", drift);
+    assert_eq!(drift.missing.into_iter().collect::<Vec<_>>(), ["[].goals"]);
+}
+
+#[test]
+fn test_detect_unknown_fields_in_any_item() {
+    let received = json!([{ "id": 1 }, { "id": 2, "country_code": "ENG" }]);
+    let decoded = json!([{ "id": 1 }]);
+
+    let drift = ModelDrift::detect(&received, &decoded);
+
+    assert_eq!(
+        drift.unknown.into_iter().collect::<Vec<_>>(),
+        ["[].country_code"]
+    );
+    assert!(drift.missing.is_empty());
+}
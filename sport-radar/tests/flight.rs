@@ -0,0 +1,102 @@
+use anyhow::{anyhow, Result};
+use sport_radar::flight::{SharedError, SingleFlight};
+use sport_radar::keys::KeysExhausted;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+async fn slow_call(calls: &AtomicU32, value: u32) -> Result<u32> {
+    calls.fetch_add(1, Ordering::SeqCst);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    Ok(value)
+}
+
+#[tokio_macros::test]
+async fn test_coalesce_identical_in_flight_calls() -> Result<()> {
+    let flights = SingleFlight::new();
+    let calls = AtomicU32::new(0);
+
+    let (a, b, c) = tokio::join!(
+        flights.run("competitions", || slow_call(&calls, 1)),
+        flights.run("competitions", || slow_call(&calls, 2)),
+        flights.run("competitions", || slow_call(&calls, 3)),
+    );
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert_eq!((a?, b?, c?), (1, 1, 1));
+    assert_eq!(flights.coalesced(), 2);
+    Ok(())
+}
+
+#[tokio_macros::test]
+async fn test_keep_distinct_calls_apart() -> Result<()> {
+    let flights = SingleFlight::new();
+    let calls = AtomicU32::new(0);
+
+    let (a, b) = tokio::join!(
+        flights.run("seasons/sr:season:1", || slow_call(&calls, 1)),
+        flights.run("seasons/sr:season:2", || slow_call(&calls, 2)),
+    );
+
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert_eq!((a?, b?), (1, 2));
+    assert_eq!(flights.coalesced(), 0);
+    Ok(())
+}
+
+#[tokio_macros::test]
+async fn test_call_again_once_completed() -> Result<()> {
+    let flights = SingleFlight::new();
+    let calls = AtomicU32::new(0);
+
+    flights.run("competitions", || slow_call(&calls, 1)).await?;
+    let value = flights.run("competitions", || slow_call(&calls, 2)).await?;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert_eq!(value, 2);
+    Ok(())
+}
+
+#[tokio_macros::test]
+async fn test_share_errors() {
+    let flights = SingleFlight::new();
+
+    let failing = || async {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        Err::<u32, _>(anyhow!("[403] API key rejected"))
+    };
+    let (a, b) = tokio::join!(
+        flights.run("competitions", failing),
+        flights.run("competitions", failing),
+    );
+
+    assert_eq!(a.unwrap_err().to_string(), "[403] API key rejected");
+    assert_eq!(b.unwrap_err().to_string(), "[403] API key rejected");
+}
+
+#[tokio_macros::test]
+async fn test_share_errors_keeping_their_type() {
+    let flights = SingleFlight::new();
+
+    let failing = || async {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        Err::<u32, _>(anyhow::Error::new(KeysExhausted {
+            retry_in: Duration::from_secs(60),
+            quota: true,
+        }))
+    };
+    let (a, b) = tokio::join!(
+        flights.run("competitions", failing),
+        flights.run("competitions", failing),
+    );
+
+    for error in [a.unwrap_err(), b.unwrap_err()] {
+        let exhausted = error
+            .downcast_ref::<SharedError>()
+            .and_then(|shared| shared.downcast_ref::<KeysExhausted>());
+        assert!(
+            exhausted.is_some_and(|exhausted| exhausted.quota),
+            "{}",
+            error
+        );
+    }
+}
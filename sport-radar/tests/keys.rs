@@ -0,0 +1,100 @@
+use anyhow::Result;
+use sport_radar::keys::{fingerprint, redact, KeyPool, QUOTA_COOLDOWN, RATE_COOLDOWN};
+use std::time::{Duration, Instant};
+
+fn pool() -> KeyPool {
+    KeyPool::new(&["key-1".to_string(), "key-2".to_string()])
+}
+
+#[test]
+fn test_keep_using_the_current_key() -> Result<()> {
+    let mut keys = pool();
+    let now = Instant::now();
+
+    assert_eq!(keys.acquire(now)?.1, "key-1");
+    assert_eq!(keys.acquire(now)?.1, "key-1");
+    assert_eq!(keys.usage(now)[0].requests, 2);
+    Ok(())
+}
+
+#[test]
+fn test_rotate_on_rejection() -> Result<()> {
+    let mut keys = pool();
+    let now = Instant::now();
+
+    let (index, _) = keys.acquire(now)?;
+    keys.reject(index, QUOTA_COOLDOWN, now);
+
+    assert_eq!(keys.acquire(now)?.1, "key-2");
+    let usage = keys.usage(now);
+    assert_eq!(usage[0].rejections, 1);
+    assert!(usage[0].cooling_down);
+    assert_eq!(usage[0].key, "key-****");
+    Ok(())
+}
+
+#[test]
+fn test_exhausted_until_cooldown_ends() -> Result<()> {
+    let mut keys = pool();
+    let now = Instant::now();
+
+    for _ in 0..2 {
+        let (index, _) = keys.acquire(now)?;
+        keys.reject(index, RATE_COOLDOWN, now);
+    }
+
+    let exhausted = keys.acquire(now).expect_err("Every key is cooling down");
+    assert_eq!(exhausted.retry_in, RATE_COOLDOWN);
+    assert!(!exhausted.quota, "Rate rejected keys still have quota");
+
+    let later = now + RATE_COOLDOWN + Duration::from_millis(1);
+    assert!(keys.acquire(later).is_ok(), "Keys are back after cooldown");
+    Ok(())
+}
+
+#[test]
+fn test_exhausted_quota_once_every_key_is_rejected_for_it() -> Result<()> {
+    let mut keys = pool();
+    let now = Instant::now();
+
+    let (index, _) = keys.acquire(now)?;
+    keys.reject(index, RATE_COOLDOWN, now);
+    let (index, _) = keys.acquire(now)?;
+    keys.reject(index, QUOTA_COOLDOWN, now);
+    assert!(
+        !keys
+            .acquire(now)
+            .expect_err("Every key is cooling down")
+            .quota
+    );
+
+    let later = now + RATE_COOLDOWN + Duration::from_millis(1);
+    let (index, _) = keys.acquire(later)?;
+    keys.reject(index, QUOTA_COOLDOWN, later);
+    assert!(
+        keys.acquire(later)
+            .expect_err("Every key is out of quota")
+            .quota
+    );
+    Ok(())
+}
+
+#[test]
+fn test_redact_api_keys_from_urls() -> Result<()> {
+    let error = "error sending request for url (https://api.sportradar.com/soccer/trial/v4/en/competitions.json?api_key=secret-key)";
+
+    let redacted = redact(error);
+
+    assert!(!redacted.contains("secret-key"), "{}", redacted);
+    assert!(redacted.ends_with("?api_key=secr****)"), "{}", redacted);
+    Ok(())
+}
+
+#[test]
+fn test_fingerprint_keys_stably() -> Result<()> {
+    assert_eq!(fingerprint("key-1"), fingerprint("key-1"));
+    assert_ne!(fingerprint("key-1"), fingerprint("key-2"));
+    assert_eq!(fingerprint(""), "cbf29ce484222325");
+    assert!(!fingerprint("secret-key").contains("secr"));
+    Ok(())
+}
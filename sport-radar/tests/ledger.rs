@@ -0,0 +1,134 @@
+use anyhow::Result;
+use chrono::{NaiveDate, TimeZone, Utc};
+use sport_radar::keys::{fingerprint, mask};
+use sport_radar::ledger::{Ledger, QuotaReport, RequestRecord};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{env, fs};
+
+/// A ledger path in a new directory, unique to the calling test.
+fn ledger_path() -> PathBuf {
+    static COUNT: AtomicU32 = AtomicU32::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .subsec_nanos();
+    let dir = env::temp_dir().join(format!(
+        "sport-cli-ledger-{}-{}-{}",
+        std::process::id(),
+        nanos,
+        COUNT.fetch_add(1, Ordering::SeqCst)
+    ));
+    fs::create_dir_all(&dir).unwrap();
+    dir.join("ledger.jsonl")
+}
+
+fn record(day: u32, endpoint: &str, status: u16) -> RequestRecord {
+    RequestRecord {
+        timestamp: Utc.with_ymd_and_hms(2024, 8, day, 12, 0, 0).unwrap(),
+        key: fingerprint("key-1"),
+        endpoint: endpoint.to_string(),
+        status,
+        latency_ms: 100,
+    }
+}
+
+#[test]
+fn test_persist_records() -> Result<()> {
+    let path = ledger_path();
+    let ledger = Ledger::new(path.clone());
+
+    ledger.append(&record(1, "competitions", 200))?;
+    ledger.append(&record(2, "competition_seasons", 200))?;
+
+    let reopened = Ledger::new(path.clone());
+    assert_eq!(reopened.records()?.len(), 2);
+    let day = NaiveDate::from_ymd_opt(2024, 8, 15).unwrap();
+    assert_eq!(reopened.used_in_month(&fingerprint("key-1"), day)?, 2);
+    assert_eq!(reopened.used_in_month(&fingerprint("other"), day)?, 0);
+
+    fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn test_tell_keys_with_the_same_prefix_apart() -> Result<()> {
+    let ledger = Ledger::new(ledger_path());
+    let day = NaiveDate::from_ymd_opt(2024, 8, 15).unwrap();
+    assert_eq!(mask("key-1"), mask("key-2"));
+
+    ledger.append(&record(1, "competitions", 200))?;
+
+    assert_eq!(ledger.used_in_month(&fingerprint("key-1"), day)?, 1);
+    assert_eq!(ledger.used_in_month(&fingerprint("key-2"), day)?, 0);
+    Ok(())
+}
+
+#[test]
+fn test_count_records_appended_after_reading() -> Result<()> {
+    let ledger = Ledger::new(ledger_path());
+    let day = NaiveDate::from_ymd_opt(2024, 8, 15).unwrap();
+    ledger.append(&record(1, "competitions", 200))?;
+    assert_eq!(ledger.used_in_month(&fingerprint("key-1"), day)?, 1);
+
+    ledger.append(&record(2, "competition_seasons", 200))?;
+    ledger.append(&RequestRecord {
+        timestamp: Utc.with_ymd_and_hms(2024, 9, 1, 0, 0, 0).unwrap(),
+        ..record(1, "competitions", 200)
+    })?;
+
+    assert_eq!(ledger.used_in_month(&fingerprint("key-1"), day)?, 2);
+    Ok(())
+}
+
+#[test]
+fn test_quota_report() -> Result<()> {
+    let records = vec![
+        record(1, "competitions", 200),
+        record(15, "seasonal_competitor_statistics", 200),
+        record(15, "seasonal_competitor_statistics", 403),
+        // A new month should not be counted
+        RequestRecord {
+            timestamp: Utc.with_ymd_and_hms(2024, 9, 1, 0, 0, 0).unwrap(),
+            ..record(1, "competitions", 200)
+        },
+    ];
+
+    let report = QuotaReport::new(&records, NaiveDate::from_ymd_opt(2024, 8, 15).unwrap());
+
+    let quota = &report.keys[&fingerprint("key-1")];
+    assert_eq!(quota.month, 3);
+    assert_eq!(quota.today, 2);
+    let statistics = &quota.endpoints["seasonal_competitor_statistics"];
+    assert_eq!(statistics.requests, 2);
+    assert_eq!(statistics.failures, 1);
+    assert_eq!(statistics.average_latency_ms(), 100);
+    assert_eq!(report.month_total(), 3);
+    Ok(())
+}
+
+#[test]
+fn test_do_not_count_requests_without_response_against_the_quota() -> Result<()> {
+    let ledger = Ledger::new(ledger_path());
+    let day = NaiveDate::from_ymd_opt(2024, 8, 15).unwrap();
+    ledger.append(&record(15, "competitions", 200))?;
+    ledger.append(&record(15, "competitions", 0))?;
+    assert_eq!(ledger.used_in_month(&fingerprint("key-1"), day)?, 1);
+
+    ledger.append(&record(15, "competitions", 0))?;
+    assert_eq!(ledger.used_in_month(&fingerprint("key-1"), day)?, 1);
+    let reopened = Ledger::new(ledger.path().to_path_buf());
+    assert_eq!(reopened.used_in_month(&fingerprint("key-1"), day)?, 1);
+
+    // Still recorded, and reported as failed attempts
+    let records = ledger.records()?;
+    assert_eq!(records.len(), 3);
+    let report = QuotaReport::new(&records, day);
+    let quota = &report.keys[&fingerprint("key-1")];
+    assert_eq!((quota.month, quota.today), (1, 1));
+    let competitions = &quota.endpoints["competitions"];
+    assert_eq!((competitions.requests, competitions.failures), (3, 2));
+    assert_eq!(report.month_total(), 1);
+    Ok(())
+}
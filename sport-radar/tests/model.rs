@@ -1,5 +1,6 @@
 use anyhow::Result;
 use serde_json::from_str;
+use sport_radar::config::Format;
 use sport_radar::model::{
     CompetitionsResponse, CompetitorsResponse, PlayerStatisticsResponse, SeasonsResponse,
 };
@@ -50,3 +51,121 @@ fn test_parse_player_statistics() -> Result<()> {
     // println!("{:?}", player_statistics);
     Ok(())
 }
+
+#[test]
+fn test_parse_competitions_xml() -> Result<()> {
+    let json = fs::read_to_string(load_resource("competitions.json")?)?;
+    let xml = fs::read_to_string(load_resource("competitions.xml")?)?;
+    let from_json: CompetitionsResponse = Format::Json.decode(&json)?;
+    let from_xml: CompetitionsResponse = Format::Xml.decode(&xml)?;
+    assert_eq!(from_json, from_xml);
+    Ok(())
+}
+
+#[test]
+fn test_parse_seasons_xml() -> Result<()> {
+    let json = fs::read_to_string(load_resource("competition_seasons.json")?)?;
+    let xml = fs::read_to_string(load_resource("competition_seasons.xml")?)?;
+    let from_json: SeasonsResponse = Format::Json.decode(&json)?;
+    let from_xml: SeasonsResponse = Format::Xml.decode(&xml)?;
+    assert_eq!(from_json, from_xml);
+    Ok(())
+}
+
+#[test]
+fn test_parse_competitor_xml() -> Result<()> {
+    let json = fs::read_to_string(load_resource("season_competitors.json")?)?;
+    let xml = fs::read_to_string(load_resource("season_competitors.xml")?)?;
+    let from_json: CompetitorsResponse = Format::Json.decode(&json)?;
+    let from_xml: CompetitorsResponse = Format::Xml.decode(&xml)?;
+    assert_eq!(from_json, from_xml);
+    Ok(())
+}
+
+#[test]
+fn test_parse_player_statistics_xml() -> Result<()> {
+    let json = fs::read_to_string(load_resource("seasonal_competitor_statistics.json")?)?;
+    let xml = fs::read_to_string(load_resource("seasonal_competitor_statistics.xml")?)?;
+    let from_json: PlayerStatisticsResponse = Format::Json.decode(&json)?;
+    let from_xml: PlayerStatisticsResponse = Format::Xml.decode(&xml)?;
+    assert_eq!(from_json, from_xml);
+    Ok(())
+}
+
+#[test]
+fn test_parse_seasons_with_malformed_date() -> Result<()> {
+    let path = load_resource("competition_seasons.json")?;
+    let file_content = fs::read_to_string(path)?.replacen("2023-08-11", "2023-13-11", 1);
+    let seasons = from_str::<SeasonsResponse>(&file_content);
+    assert!(seasons.is_err(), "Malformed dates should fail to parse");
+    Ok(())
+}
+
+#[test]
+fn test_detect_unknown_fields() -> Result<()> {
+    let path = load_resource("competitions.json")?;
+    let file_content = fs::read_to_string(path)?;
+    let (_, drift) = Format::Json.decode_with_drift::<CompetitionsResponse>(&file_content)?;
+    assert!(drift
+        .unknown
+        .contains("competitions[].category.country_code"));
+    assert!(drift.missing.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_keep_players_with_missing_statistics() -> Result<()> {
+    let path = load_resource("seasonal_competitor_statistics.json")?;
+    let file_content = fs::read_to_string(path)?;
+    let expected: PlayerStatisticsResponse = from_str(&file_content)?;
+    let file_content = file_content.replacen("\"goals_by_head\": 0,", "", 1);
+
+    let (decoded, drift) =
+        Format::Json.decode_with_drift::<PlayerStatisticsResponse>(&file_content)?;
+
+    // The incomplete player is kept, with the statistic left unreported rather than zero
+    let players = &decoded.competitor.players;
+    assert_eq!(players.len(), expected.competitor.players.len());
+    assert_eq!(players[0].statistics.goals_by_head, None);
+    assert_eq!(
+        players[0].statistics.goals_scored,
+        expected.competitor.players[0].statistics.goals_scored
+    );
+    assert_eq!(players[1..], expected.competitor.players[1..]);
+    assert!(drift
+        .missing
+        .contains("competitor.players[].statistics.goals_by_head"));
+    assert!(drift
+        .unknown
+        .contains("competitor.players[].statistics.shots_on_target"));
+    Ok(())
+}
+
+#[test]
+fn test_keep_players_with_missing_statistics_xml() -> Result<()> {
+    let json = fs::read_to_string(load_resource("seasonal_competitor_statistics.json")?)?;
+    let expected: PlayerStatisticsResponse = from_str(&json)?;
+    let xml = fs::read_to_string(load_resource("seasonal_competitor_statistics.xml")?)?.replacen(
+        "goals_by_head=\"0\"",
+        "",
+        1,
+    );
+
+    let decoded: PlayerStatisticsResponse = Format::Xml.decode(&xml)?;
+
+    let players = &decoded.competitor.players;
+    assert_eq!(players.len(), expected.competitor.players.len());
+    assert_eq!(players[0].statistics.goals_by_head, None);
+    assert_eq!(players[1..], expected.competitor.players[1..]);
+    Ok(())
+}
+
+#[test]
+fn test_detect_drift_rejects_xml() -> Result<()> {
+    let xml = fs::read_to_string(load_resource("competitions.xml")?)?;
+    let error = Format::Xml
+        .decode_with_drift::<CompetitionsResponse>(&xml)
+        .unwrap_err();
+    assert!(error.to_string().contains("--format json"), "{}", error);
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use anyhow::Result;
+use sport_radar::rate::{interval, RateLimiter};
+use std::time::{Duration, Instant};
+
+#[tokio_macros::test]
+async fn test_space_requests_by_rate_limit() -> Result<()> {
+    let rate_limiter = RateLimiter::new(20.0)?;
+    let started_at = Instant::now();
+
+    for _ in 0..3 {
+        rate_limiter.acquire().await;
+    }
+
+    // The first slot is immediate, the next two wait 50ms each
+    assert!(started_at.elapsed() >= Duration::from_millis(100));
+    Ok(())
+}
+
+#[tokio_macros::test]
+async fn test_unlimited_rate() -> Result<()> {
+    let rate_limiter = RateLimiter::new(0.0)?;
+    let started_at = Instant::now();
+
+    for _ in 0..100 {
+        rate_limiter.acquire().await;
+    }
+
+    assert!(started_at.elapsed() < Duration::from_millis(100));
+    Ok(())
+}
+
+#[test]
+fn test_reject_invalid_rate_limits() -> Result<()> {
+    assert_eq!(interval(0.5)?, Duration::from_secs(2));
+    for rate_limit in [-1.0, f64::NAN, f64::MIN_POSITIVE, f64::NEG_INFINITY] {
+        let error = interval(rate_limit).expect_err("Invalid rate limit");
+        assert!(
+            error.to_string().contains("Invalid rate limit"),
+            "{}",
+            error
+        );
+    }
+    assert_eq!(interval(f64::INFINITY)?, Duration::ZERO);
+    Ok(())
+}
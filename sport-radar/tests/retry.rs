@@ -0,0 +1,93 @@
+use anyhow::Result;
+use sport_radar::client::SportRadarClient;
+use sport_radar::config::ConfigLayer;
+use sport_radar::testing::MockServer;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+fn client(base_url: &str, api_key: &str) -> Result<SportRadarClient> {
+    let config = ConfigLayer {
+        api_key: Some(api_key.to_string()),
+        rate_limit: Some(0.0),
+        ..Default::default()
+    }
+    .resolve()?;
+    Ok(SportRadarClient::new(config).with_base_url(base_url))
+}
+
+#[tokio_macros::test]
+async fn test_wait_for_a_single_key_after_a_rate_rejection() -> Result<()> {
+    let competitions = include_str!("../resources/api/competitions.json");
+    let calls = AtomicU32::new(0);
+    let server = MockServer::start(move |_| match calls.fetch_add(1, Ordering::SeqCst) {
+        0 => (429, "Too many requests".to_string()),
+        _ => (200, competitions.to_string()),
+    })
+    .await?;
+
+    let response = client(server.base_url(), "the-only-key")?
+        .get_competitions()
+        .await?;
+
+    assert!(!response.competitions.is_empty());
+    assert_eq!(
+        server.requests(),
+        vec!["/trial/v4/en/competitions.json"; 2],
+        "The request is sent again once the key cooled down"
+    );
+    Ok(())
+}
+
+#[tokio_macros::test]
+async fn test_give_up_when_every_key_is_out_of_quota() -> Result<()> {
+    let server = MockServer::start(|_| (403, "Quota exceeded".to_string())).await?;
+
+    let error = client(server.base_url(), "the-only-key")?
+        .get_competitions()
+        .await
+        .expect_err("No key has quota left");
+
+    assert!(error.to_string().contains("exhausted"), "{}", error);
+    assert_eq!(server.requests().len(), 1);
+    Ok(())
+}
+
+#[tokio_macros::test]
+async fn test_do_not_retry_a_not_found() -> Result<()> {
+    let server = MockServer::start(|_| (404, "Not found".to_string())).await?;
+
+    let error = client(server.base_url(), "the-only-key")?
+        .get_competitions()
+        .await
+        .expect_err("Nothing to find");
+
+    assert!(error.to_string().contains("404"), "{}", error);
+    assert_eq!(server.requests().len(), 1, "A 404 would fail again");
+    Ok(())
+}
+
+#[tokio_macros::test]
+async fn test_do_not_retry_an_undecodable_response() -> Result<()> {
+    let server = MockServer::start(|_| (200, "{ \"competitions\": 17 }".to_string())).await?;
+
+    client(server.base_url(), "the-only-key")?
+        .get_competitions()
+        .await
+        .expect_err("Not a list of competitions");
+
+    assert_eq!(server.requests().len(), 1);
+    Ok(())
+}
+
+#[tokio_macros::test]
+async fn test_retry_server_errors_up_to_the_limit() -> Result<()> {
+    let server = MockServer::start(|_| (503, "Unavailable".to_string())).await?;
+
+    client(server.base_url(), "the-only-key")?
+        .with_max_retries(2)
+        .get_competitions()
+        .await
+        .expect_err("The provider keeps failing");
+
+    assert_eq!(server.requests().len(), 3, "The request and 2 retries");
+    Ok(())
+}
@@ -4,6 +4,7 @@ use std::pin::Pin;
 use std::sync::Arc;
 use tokio::sync::{broadcast, mpsc};
 use tokio::time::Duration;
+use tracing::{debug, error, info_span, Instrument};
 
 pub type ConsumerCallback<T> =
     dyn Fn(T) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync + 'static;
@@ -54,7 +55,7 @@ impl<T: Send + Sync + 'static> Consumer<T> {
 
         tokio::spawn(async move {
             if let Err(e) = consumer.run().await {
-                eprintln!("Consumer failed: {:?}", e);
+                error!(error = ?e, "Consumer failed");
             }
         })
     }
@@ -67,6 +68,7 @@ impl<T> Consumer<T> {
         let mut interval_timer = tokio::time::interval(interval);
 
         let mut shutdown_rx = self.shutdown_tx.subscribe();
+        let mut message_seq: u64 = 0;
 
         loop {
             tokio::select! {
@@ -77,11 +79,14 @@ impl<T> Consumer<T> {
                         self.rx.recv().await {
                             // Execute the callback
                             let consumer_callback = Arc::clone(&self.consumer_callback);
-                            consumer_callback(data).await;
+                            let span = info_span!("consumer_message", seq = message_seq);
+                            message_seq += 1;
+                            consumer_callback(data).instrument(span).await;
                         }
                 }
                 _ = shutdown_rx.recv() => {
                     // Shutdown signal received
+                    debug!(messages = message_seq, "Consumer shutting down");
                     break;
                 }
             }
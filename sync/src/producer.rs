@@ -4,6 +4,7 @@ use std::pin::Pin;
 use std::sync::Arc;
 use tokio::sync::{broadcast, mpsc};
 use tokio::time::Duration;
+use tracing::{debug, error};
 
 pub type ProducerCallback<T> =
     dyn Fn() -> Pin<Box<dyn Future<Output = T> + Send>> + Send + Sync + 'static;
@@ -49,7 +50,7 @@ impl<T: Send + Sync + 'static> Producer<T> {
 
         tokio::spawn(async move {
             if let Err(e) = producer.run().await {
-                eprintln!("Producer failed: {:?}", e);
+                error!(error = ?e, "Producer failed");
             }
         })
     }
@@ -78,6 +79,7 @@ impl<T> Producer<T> {
                 }
                 _ = shutdown_rx.recv() => {
                     // Shutdown signal received
+                    debug!("Producer shutting down");
                     break;
                 }
             }